@@ -0,0 +1,179 @@
+//! Integration with the Bevy game engine.
+//!
+//! `HexArray` can be inserted as a `Resource`, entities can be placed on tiles with the `Tile`
+//! component, and `HexLayout` converts between tile indices and world positions. Add
+//! `HexArrayPlugin` to keep the `Transform` of every `Tile` in sync with its indices.
+
+use bevy_app::{App, Plugin, PostUpdate};
+use bevy_camera::Camera;
+use bevy_ecs::prelude::*;
+use bevy_math::{Vec2, Vec3};
+use bevy_transform::components::{GlobalTransform, Transform};
+use bevy_transform::TransformSystems;
+use bevy_window::Window;
+
+use crate::{pos, HexArray};
+
+impl<T> Resource for HexArray<T> where T: Send + Sync + 'static {}
+
+/// The indices of the tile an entity is placed on.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Tile {
+    pub x: usize,
+    pub y: usize,
+}
+
+/// The placement of tiles in the world.
+///
+/// Tiles are laid out in the xy plane with the geometry of `HexArray::position`, scaled by `size`
+/// and moved by `origin`.
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct HexLayout {
+    /// The distance from the center of a tile to its corners.
+    pub size: f32,
+    /// The world position of the center of the tile at (0, 0).
+    pub origin: Vec2,
+}
+
+impl Default for HexLayout {
+    fn default() -> Self {
+        HexLayout {
+            size: 1.0,
+            origin: Vec2::ZERO,
+        }
+    }
+}
+
+impl HexLayout {
+    /// Get the world position of the center of the tile at the given indices.
+    pub fn world_position(&self, x: usize, y: usize) -> Vec2 {
+        let (x, y) = pos(x as isize, y as isize);
+        self.origin + Vec2::new(x, y) * self.size
+    }
+
+    /// Get a transform placing an entity at the center of the tile at the given indices.
+    pub fn transform(&self, x: usize, y: usize) -> Transform {
+        Transform::from_translation(self.world_position(x, y).extend(0.0))
+    }
+
+    /// Get the indices of the tile of the HexArray containing the given world position.
+    pub fn tile_at<T>(&self, hex_array: &HexArray<T>, position: Vec2) -> Option<(usize, usize)> {
+        let position = (position - self.origin) / self.size;
+        hex_array.tile_at(position.x, position.y)
+    }
+
+    /// Get the indices of the tile of the HexArray under the translation of a transform.
+    pub fn tile_at_transform<T>(
+        &self,
+        hex_array: &HexArray<T>,
+        transform: &Transform,
+    ) -> Option<(usize, usize)> {
+        self.tile_at(hex_array, transform.translation.truncate())
+    }
+
+    /// Get the indices of the tile of the HexArray under the cursor of the window, as seen by a
+    /// 2D camera.
+    pub fn cursor_tile<T>(
+        &self,
+        hex_array: &HexArray<T>,
+        window: &Window,
+        camera: &Camera,
+        camera_transform: &GlobalTransform,
+    ) -> Option<(usize, usize)> {
+        let cursor = window.cursor_position()?;
+        let position = camera.viewport_to_world_2d(camera_transform, cursor).ok()?;
+        self.tile_at(hex_array, position)
+    }
+}
+
+/// Move every entity with a changed `Tile` to the center of its tile, keeping its z translation.
+///
+/// Every entity is moved when the `HexLayout` changes.
+pub fn sync_tile_transforms(layout: Res<HexLayout>, mut query: Query<(Ref<Tile>, &mut Transform)>) {
+    for (tile, mut transform) in &mut query {
+        if tile.is_changed() || layout.is_changed() {
+            let Vec3 { z, .. } = transform.translation;
+            transform.translation = layout.world_position(tile.x, tile.y).extend(z);
+        }
+    }
+}
+
+/// Keep the `Transform` of every entity with a `Tile` in sync with its indices.
+pub struct HexArrayPlugin;
+
+impl Plugin for HexArrayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HexLayout>().add_systems(
+            PostUpdate,
+            sync_tile_transforms.before(TransformSystems::Propagate),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // HexLayout tests
+
+    #[test]
+    fn test_world_position() {
+        let layout = HexLayout {
+            size: 10.0,
+            origin: Vec2::new(100.0, 0.0),
+        };
+        let position = layout.world_position(1, 0);
+        assert!((position - Vec2::new(115.0, 8.660254)).length() < 1e-4);
+        assert_eq!(
+            layout.transform(0, 0).translation,
+            Vec3::new(100.0, 0.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_tile_at() {
+        let hex_array = HexArray::new(4, 4, 0);
+        let layout = HexLayout {
+            size: 32.0,
+            origin: Vec2::new(-50.0, 20.0),
+        };
+        for x in 0..4 {
+            for y in 0..4 {
+                let transform = layout.transform(x, y);
+                assert_eq!(
+                    layout.tile_at_transform(&hex_array, &transform),
+                    Some((x, y))
+                );
+            }
+        }
+        assert_eq!(layout.tile_at(&hex_array, Vec2::new(-100.0, 20.0)), None);
+        let empty = HexLayout {
+            size: 0.0,
+            origin: Vec2::ZERO,
+        };
+        assert_eq!(empty.tile_at(&hex_array, Vec2::ZERO), None);
+        assert_eq!(empty.tile_at(&hex_array, Vec2::ONE), None);
+    }
+
+    // HexArrayPlugin tests
+
+    #[test]
+    fn test_sync_tile_transforms() {
+        let mut app = App::new();
+        app.add_plugins(HexArrayPlugin);
+        app.insert_resource(HexArray::new(3, 3, 0u8));
+        let entity = app
+            .world_mut()
+            .spawn((Tile { x: 0, y: 1 }, Transform::from_xyz(0.0, 0.0, 5.0)))
+            .id();
+        app.update();
+        let transform = app.world().get::<Transform>(entity).unwrap();
+        assert!((transform.translation - Vec3::new(0.0, 1.7320508, 5.0)).length() < 1e-5);
+
+        app.world_mut().resource_mut::<HexLayout>().size = 2.0;
+        app.update();
+        let transform = app.world().get::<Transform>(entity).unwrap();
+        assert!((transform.translation - Vec3::new(0.0, 3.4641016, 5.0)).length() < 1e-5);
+        assert_eq!(app.world().resource::<HexArray<u8>>().get(2, 2), Some(&0));
+    }
+}
@@ -0,0 +1,365 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::HexArray;
+
+const BITS: usize = u64::BITS as usize;
+
+/// A HexArray of booleans storing one bit per tile, for masks such as visibility or explored tiles.
+///
+/// Every row of tiles sharing an x index is padded to whole words, so set operations, counting,
+/// dilation and erosion work on 64 tiles at a time.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct HexBitArray {
+    height: usize,
+    width: usize,
+    words: Vec<u64>,
+}
+
+impl HexBitArray {
+    /// Create a new HexBitArray with the given height, width, and default value.
+    pub fn new(height: usize, width: usize, default: bool) -> Self {
+        let mut result = HexBitArray {
+            height,
+            width,
+            words: vec![0; height * width.div_ceil(BITS)],
+        };
+        if default {
+            result.fill(true);
+        }
+        result
+    }
+
+    /// Get the height of the HexBitArray.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Get the width of the HexBitArray.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    fn row_words(&self) -> usize {
+        self.width.div_ceil(BITS)
+    }
+
+    /// Get the index of the word and the bit within it for the given indices.
+    fn bit(&self, x: usize, y: usize) -> Option<(usize, u64)> {
+        if x < self.height && y < self.width {
+            Some((x * self.row_words() + y / BITS, 1 << (y % BITS)))
+        } else {
+            None
+        }
+    }
+
+    /// Clear the padding bits after the last tile of every row.
+    fn mask(&mut self) {
+        let used = self.width % BITS;
+        if used != 0 {
+            let row_words = self.row_words();
+            for row in self.words.chunks_exact_mut(row_words) {
+                row[row_words - 1] &= !0 >> (BITS - used);
+            }
+        }
+    }
+
+    /// Get the value at the given indices.
+    pub fn get(&self, x: usize, y: usize) -> Option<bool> {
+        let (word, bit) = self.bit(x, y)?;
+        Some(self.words[word] & bit != 0)
+    }
+
+    /// Set the value at the given indices.
+    pub fn set(&mut self, x: usize, y: usize, value: bool) -> Option<()> {
+        let (word, bit) = self.bit(x, y)?;
+        if value {
+            self.words[word] |= bit;
+        } else {
+            self.words[word] &= !bit;
+        }
+        Some(())
+    }
+
+    /// Set every tile to the given value.
+    pub fn fill(&mut self, value: bool) {
+        self.words.fill(if value { !0 } else { 0 });
+        self.mask();
+    }
+
+    /// Get the number of set tiles.
+    pub fn count(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Flip every tile.
+    pub fn invert(&mut self) {
+        for word in &mut self.words {
+            *word = !*word;
+        }
+        self.mask();
+    }
+
+    fn combine<F>(&mut self, other: &HexBitArray, f: F) -> Option<()>
+    where
+        F: Fn(u64, u64) -> u64,
+    {
+        if self.height != other.height || self.width != other.width {
+            return None;
+        }
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word = f(*word, *other);
+        }
+        Some(())
+    }
+
+    /// Set every tile that is set in the other HexBitArray, if the dimensions match.
+    pub fn union_with(&mut self, other: &HexBitArray) -> Option<()> {
+        self.combine(other, |a, b| a | b)
+    }
+
+    /// Clear every tile that is not set in the other HexBitArray, if the dimensions match.
+    pub fn intersect_with(&mut self, other: &HexBitArray) -> Option<()> {
+        self.combine(other, |a, b| a & b)
+    }
+
+    /// Clear every tile that is set in the other HexBitArray, if the dimensions match.
+    pub fn difference_with(&mut self, other: &HexBitArray) -> Option<()> {
+        self.combine(other, |a, b| a & !b)
+    }
+
+    /// Flip every tile that is set in the other HexBitArray, if the dimensions match.
+    pub fn xor_with(&mut self, other: &HexBitArray) -> Option<()> {
+        self.combine(other, |a, b| a ^ b)
+    }
+
+    /// Get a HexBitArray with every set tile and the tiles adjacent to them set.
+    pub fn dilate(&self) -> HexBitArray {
+        let row_words = self.row_words();
+        let mut result = self.clone();
+        for x in 0..self.height {
+            let row = &self.words[x * row_words..(x + 1) * row_words];
+            let out = &mut result.words[x * row_words..(x + 1) * row_words];
+            shift_up(row, out);
+            shift_down(row, out);
+            for nx in [x.wrapping_sub(1), x + 1] {
+                if nx >= self.height {
+                    continue;
+                }
+                // even rows touch y - 1 and y of their neighbors, odd rows y and y + 1
+                let adjacent = &self.words[nx * row_words..(nx + 1) * row_words];
+                for (word, adjacent) in out.iter_mut().zip(adjacent) {
+                    *word |= adjacent;
+                }
+                if x.is_multiple_of(2) {
+                    shift_up(adjacent, out);
+                } else {
+                    shift_down(adjacent, out);
+                }
+            }
+        }
+        result.mask();
+        result
+    }
+
+    /// Get a HexBitArray with only the set tiles whose adjacent tiles are all set.
+    ///
+    /// Tiles outside the HexBitArray count as set, so the edge does not erode the mask.
+    pub fn erode(&self) -> HexBitArray {
+        let mut result = self.clone();
+        result.invert();
+        let mut result = result.dilate();
+        result.invert();
+        result
+    }
+
+    /// Iterate over the indices of every set tile.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let row_words = self.row_words();
+        self.words.iter().enumerate().flat_map(move |(i, &word)| {
+            let (x, offset) = (i / row_words, i % row_words * BITS);
+            let mut word = word;
+            core::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some((x, offset + bit))
+            })
+        })
+    }
+}
+
+/// Set the bit for y in `out` wherever the bit for y - 1 is set in `row`.
+fn shift_up(row: &[u64], out: &mut [u64]) {
+    for i in 0..row.len() {
+        let carry = if i > 0 { row[i - 1] >> (BITS - 1) } else { 0 };
+        out[i] |= row[i] << 1 | carry;
+    }
+}
+
+/// Set the bit for y in `out` wherever the bit for y + 1 is set in `row`.
+fn shift_down(row: &[u64], out: &mut [u64]) {
+    for i in 0..row.len() {
+        let carry = if i + 1 < row.len() {
+            row[i + 1] << (BITS - 1)
+        } else {
+            0
+        };
+        out[i] |= row[i] >> 1 | carry;
+    }
+}
+
+impl From<&HexArray<bool>> for HexBitArray {
+    fn from(hex_array: &HexArray<bool>) -> Self {
+        let mut result = HexBitArray::new(hex_array.height, hex_array.width, false);
+        for x in 0..hex_array.height {
+            for y in 0..hex_array.width {
+                if hex_array.tiles[x * hex_array.width + y] {
+                    result.set(x, y, true);
+                }
+            }
+        }
+        result
+    }
+}
+
+impl From<&HexBitArray> for HexArray<bool> {
+    fn from(bit_array: &HexBitArray) -> Self {
+        let mut tiles = Vec::with_capacity(bit_array.height * bit_array.width);
+        for x in 0..bit_array.height {
+            for y in 0..bit_array.width {
+                tiles.push(bit_array.get(x, y).unwrap());
+            }
+        }
+        HexArray {
+            height: bit_array.height,
+            width: bit_array.width,
+            tiles,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A mask spanning several words per row with an irregular pattern of set tiles.
+    fn pattern(height: usize, width: usize) -> HexArray<bool> {
+        let mut hex_array = HexArray::new(height, width, false);
+        for x in 0..height {
+            for y in 0..width {
+                hex_array.set(x, y, (x * 7 + y * 13) % 11 == 0);
+            }
+        }
+        hex_array
+    }
+
+    // get tests
+
+    #[test]
+    fn test_get_set() {
+        let mut bits = HexBitArray::new(3, 70, false);
+        assert_eq!(bits.set(2, 69, true), Some(()));
+        assert_eq!(bits.set(3, 0, true), None);
+        assert_eq!(bits.get(2, 69), Some(true));
+        assert_eq!(bits.get(2, 68), Some(false));
+        assert_eq!(bits.get(0, 70), None);
+        bits.set(2, 69, false);
+        assert_eq!(bits.count(), 0);
+    }
+
+    #[test]
+    fn test_fill_count() {
+        let mut bits = HexBitArray::new(3, 70, true);
+        assert_eq!(bits.count(), 210);
+        bits.invert();
+        assert_eq!(bits.count(), 0);
+        bits.fill(true);
+        bits.set(1, 1, false);
+        bits.invert();
+        assert_eq!(bits.count(), 1);
+    }
+
+    // set operation tests
+
+    #[test]
+    fn test_set_operations() {
+        let mut a = HexBitArray::new(2, 2, false);
+        a.set(0, 0, true);
+        a.set(0, 1, true);
+        let mut b = HexBitArray::new(2, 2, false);
+        b.set(0, 1, true);
+        b.set(1, 1, true);
+        let mut union = a.clone();
+        union.union_with(&b);
+        assert_eq!(union.iter().collect::<Vec<_>>(), [(0, 0), (0, 1), (1, 1)]);
+        let mut intersection = a.clone();
+        intersection.intersect_with(&b);
+        assert_eq!(intersection.iter().collect::<Vec<_>>(), [(0, 1)]);
+        let mut difference = a.clone();
+        difference.difference_with(&b);
+        assert_eq!(difference.iter().collect::<Vec<_>>(), [(0, 0)]);
+        let mut xor = a.clone();
+        xor.xor_with(&b);
+        assert_eq!(xor.iter().collect::<Vec<_>>(), [(0, 0), (1, 1)]);
+        assert_eq!(a.union_with(&HexBitArray::new(2, 3, false)), None);
+    }
+
+    // dilate tests
+
+    #[test]
+    fn test_dilate() {
+        let hex_array = pattern(5, 130);
+        let dilated = HexArray::from(&HexBitArray::from(&hex_array).dilate());
+        for x in 0..5 {
+            for y in 0..130 {
+                let expected = hex_array.tiles[x * 130 + y]
+                    || hex_array
+                        .adjacent(x, y)
+                        .into_iter()
+                        .any(|(nx, ny)| hex_array.tiles[nx * 130 + ny]);
+                assert_eq!(dilated.get(x, y), Some(&expected), "({x}, {y})");
+            }
+        }
+    }
+
+    #[test]
+    fn test_erode() {
+        let mut bits = HexBitArray::new(4, 4, true);
+        bits.set(1, 1, false);
+        let eroded = bits.erode();
+        assert_eq!(eroded.count(), 16 - 7);
+        assert_eq!(eroded.get(0, 0), Some(true));
+        assert_eq!(eroded.get(2, 1), Some(false));
+        assert_eq!(eroded.get(2, 2), Some(false));
+        assert_eq!(eroded.get(2, 0), Some(true));
+    }
+
+    // iter tests
+
+    #[test]
+    fn test_iter() {
+        let hex_array = pattern(3, 100);
+        let bits = HexBitArray::from(&hex_array);
+        let expected: Vec<_> = (0..3)
+            .flat_map(|x| (0..100).map(move |y| (x, y)))
+            .filter(|(x, y)| hex_array.tiles[x * 100 + y])
+            .collect();
+        assert_eq!(bits.iter().collect::<Vec<_>>(), expected);
+        assert_eq!(bits.count(), expected.len());
+    }
+
+    // conversion tests
+
+    #[test]
+    fn test_hex_array_conversion() {
+        let hex_array = pattern(4, 67);
+        assert!(HexArray::from(&HexBitArray::from(&hex_array)) == hex_array);
+        assert!(HexBitArray::new(0, 0, true).iter().next().is_none());
+    }
+}
@@ -0,0 +1,245 @@
+use crate::{pos_to_tile_within, Direction, HexArray, Point};
+
+/// A HexArray with dimensions known at compile time, stored inline without heap allocation.
+///
+/// `H` is the height and `W` the width, with the same indexing and layout as `HexArray`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ConstHexArray<T, const H: usize, const W: usize> {
+    tiles: [[T; W]; H],
+}
+
+impl<T, const H: usize, const W: usize> ConstHexArray<T, H, W>
+where
+    T: Copy,
+{
+    /// Create a new ConstHexArray with every tile set to the default value.
+    pub const fn new(default: T) -> Self {
+        ConstHexArray {
+            tiles: [[default; W]; H],
+        }
+    }
+}
+
+impl<T, const H: usize, const W: usize> ConstHexArray<T, H, W> {
+    /// Create a new ConstHexArray from its tiles, indexed by x and then y.
+    pub const fn from_tiles(tiles: [[T; W]; H]) -> Self {
+        ConstHexArray { tiles }
+    }
+
+    /// Get the tiles of the ConstHexArray, indexed by x and then y.
+    pub const fn tiles(&self) -> &[[T; W]; H] {
+        &self.tiles
+    }
+
+    /// Get the height of the ConstHexArray.
+    pub const fn height(&self) -> usize {
+        H
+    }
+
+    /// Get the width of the ConstHexArray.
+    pub const fn width(&self) -> usize {
+        W
+    }
+
+    /// Get the value at the given indices.
+    pub const fn get(&self, x: usize, y: usize) -> Option<&T> {
+        if x < H && y < W {
+            Some(&self.tiles[x][y])
+        } else {
+            None
+        }
+    }
+
+    /// Get a mutable reference to the value at the given indices.
+    pub const fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        if x < H && y < W {
+            Some(&mut self.tiles[x][y])
+        } else {
+            None
+        }
+    }
+
+    /// Set the value at the given indices.
+    pub fn set(&mut self, x: usize, y: usize, value: T) -> Option<()> {
+        *self.get_mut(x, y)? = value;
+        Some(())
+    }
+
+    fn in_bounds(&self, x: isize, y: isize) -> Option<(usize, usize)> {
+        if (0..H as isize).contains(&x) && (0..W as isize).contains(&y) {
+            Some((x as usize, y as usize))
+        } else {
+            None
+        }
+    }
+
+    /// Get the position of the tile at the given indices.
+    pub fn position(x: usize, y: usize) -> Point {
+        HexArray::<T>::position(x, y)
+    }
+
+    /// Get the indices of the tile containing the given position.
+    pub fn tile_at(&self, x: f32, y: f32) -> Option<(usize, usize)> {
+        let (x, y) = pos_to_tile_within(H, W, x as f64, y as f64)?;
+        self.in_bounds(x, y)
+    }
+
+    /// Get the indices of the adjacent tile in the given direction.
+    pub fn neighbor(&self, x: usize, y: usize, direction: Direction) -> Option<(usize, usize)> {
+        let (x, y) = direction.step(x as isize, y as isize);
+        self.in_bounds(x, y)
+    }
+
+    /// Iterate over the indices of the tiles adjacent to the given indices, in the order of
+    /// `Direction::ALL`.
+    pub fn neighbors(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |direction| self.neighbor(x, y, direction))
+    }
+
+    /// Iterate over the indices and values of every tile.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.tiles.iter().enumerate().flat_map(|(x, column)| {
+            column
+                .iter()
+                .enumerate()
+                .map(move |(y, tile)| ((x, y), tile))
+        })
+    }
+
+    /// Iterate over the indices and mutable references to the values of every tile.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = ((usize, usize), &mut T)> {
+        self.tiles.iter_mut().enumerate().flat_map(|(x, column)| {
+            column
+                .iter_mut()
+                .enumerate()
+                .map(move |(y, tile)| ((x, y), tile))
+        })
+    }
+}
+
+impl<T, const H: usize, const W: usize> From<ConstHexArray<T, H, W>> for HexArray<T> {
+    fn from(const_hex_array: ConstHexArray<T, H, W>) -> Self {
+        HexArray {
+            height: H,
+            width: W,
+            tiles: const_hex_array.tiles.into_iter().flatten().collect(),
+        }
+    }
+}
+
+impl<T, const H: usize, const W: usize> TryFrom<HexArray<T>> for ConstHexArray<T, H, W> {
+    type Error = HexArray<T>;
+
+    /// Convert a HexArray with matching dimensions, returning it unchanged otherwise.
+    fn try_from(hex_array: HexArray<T>) -> Result<Self, HexArray<T>> {
+        if hex_array.height != H || hex_array.width != W {
+            return Err(hex_array);
+        }
+        let mut tiles = hex_array.tiles.into_iter();
+        let tiles = core::array::from_fn(|_| {
+            core::array::from_fn(|_| tiles.next().expect("tile count matches dimensions"))
+        });
+        Ok(ConstHexArray { tiles })
+    }
+}
+
+impl<T, const H: usize, const W: usize> Default for ConstHexArray<T, H, W>
+where
+    T: Default,
+{
+    fn default() -> Self {
+        ConstHexArray {
+            tiles: core::array::from_fn(|_| core::array::from_fn(|_| T::default())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOARD: ConstHexArray<u8, 3, 2> = ConstHexArray::from_tiles([[1, 2], [3, 4], [5, 6]]);
+
+    // new tests
+
+    #[test]
+    fn test_new() {
+        const EMPTY: ConstHexArray<u8, 4, 5> = ConstHexArray::new(0);
+        assert_eq!(EMPTY.height(), 4);
+        assert_eq!(EMPTY.width(), 5);
+        assert_eq!(EMPTY.get(3, 4), Some(&0));
+        assert_eq!(EMPTY.get(4, 0), None);
+        assert_eq!(ConstHexArray::<u8, 2, 2>::default(), ConstHexArray::new(0));
+    }
+
+    // get tests
+
+    #[test]
+    fn test_get_set() {
+        const CORNER: Option<&u8> = BOARD.get(2, 1);
+        assert_eq!(CORNER, Some(&6));
+        let mut board = BOARD;
+        assert_eq!(board.set(0, 1, 9), Some(()));
+        assert_eq!(board.set(0, 2, 9), None);
+        *board.get_mut(1, 0).unwrap() += 1;
+        assert_eq!(board.tiles(), &[[1, 9], [4, 4], [5, 6]]);
+    }
+
+    // neighbors tests
+
+    #[test]
+    fn test_neighbors() {
+        let board = ConstHexArray::<u8, 4, 4>::new(0);
+        let hex_array = HexArray::new(4, 4, 0u8);
+        for x in 0..4 {
+            for y in 0..4 {
+                let mut neighbors: Vec<_> = board.neighbors(x, y).collect();
+                let mut adjacent = hex_array.adjacent(x, y);
+                neighbors.sort();
+                adjacent.sort();
+                assert_eq!(neighbors, adjacent);
+            }
+        }
+    }
+
+    // position tests
+
+    #[test]
+    fn test_position() {
+        assert_eq!(
+            ConstHexArray::<u8, 2, 2>::position(1, 1),
+            HexArray::<u8>::position(1, 1)
+        );
+        assert_eq!(BOARD.tile_at(1.5, 0.8), Some((1, 0)));
+        assert_eq!(BOARD.tile_at(-5.0, 0.0), None);
+        assert_eq!(BOARD.tile_at(f32::NAN, 0.0), None);
+    }
+
+    // iter tests
+
+    #[test]
+    fn test_iter() {
+        let tiles: Vec<_> = BOARD.iter().map(|(index, tile)| (index, *tile)).collect();
+        assert_eq!(tiles[0], ((0, 0), 1));
+        assert_eq!(tiles[3], ((1, 1), 4));
+        let mut board = BOARD;
+        for ((x, _), tile) in board.iter_mut() {
+            *tile += x as u8;
+        }
+        assert_eq!(board.get(2, 0), Some(&7));
+    }
+
+    // conversion tests
+
+    #[test]
+    fn test_hex_array_conversion() {
+        let hex_array = HexArray::from(BOARD);
+        assert_eq!(hex_array.height(), 3);
+        assert_eq!(hex_array.get(1, 1), Some(&4));
+        assert_eq!(ConstHexArray::try_from(hex_array.clone()), Ok(BOARD));
+        let result = ConstHexArray::<u8, 2, 3>::try_from(hex_array);
+        assert!(result.is_err());
+    }
+}
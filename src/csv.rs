@@ -0,0 +1,326 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::error::Error;
+use core::fmt;
+
+use crate::HexArray;
+
+/// An error returned when parsing a HexArray from CSV.
+///
+/// Rows are x indices and columns are y indices, both counted from zero: row 0 is the first line
+/// of the text and column 0 its first field, unlike the 1-based numbering of spreadsheets.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CsvError<E> {
+    /// A quoted field is not closed, or is followed by other characters. The row and column are
+    /// 0-based.
+    Quote { row: usize, column: usize },
+    /// The text does not have the expected number of rows.
+    Rows { expected: usize, found: usize },
+    /// The 0-based row does not have the expected number of columns.
+    Columns {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// The field at the given 0-based row and column could not be parsed.
+    Field { row: usize, column: usize, error: E },
+}
+
+impl<E> fmt::Display for CsvError<E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CsvError::Quote { row, column } => {
+                write!(f, "invalid quoting at row {}, column {}", row, column)
+            }
+            CsvError::Rows { expected, found } => {
+                write!(f, "expected {} rows, found {}", expected, found)
+            }
+            CsvError::Columns {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "expected {} columns at row {}, found {}",
+                expected, row, found
+            ),
+            CsvError::Field { row, column, error } => {
+                write!(
+                    f,
+                    "invalid field at row {}, column {}: {}",
+                    row, column, error
+                )
+            }
+        }
+    }
+}
+
+impl<E> Error for CsvError<E> where E: fmt::Debug + fmt::Display {}
+
+/// Quote a field if it contains the delimiter, a quote or a line break, or if it is the only field
+/// of its line and empty.
+fn quote(field: &str, delimiter: char, alone: bool) -> String {
+    let special = field
+        .chars()
+        .any(|c| c == delimiter || c == '"' || c == '\n' || c == '\r');
+    if special || (alone && field.is_empty()) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Split the text into records of fields, following RFC 4180 quoting.
+///
+/// An empty line is a record without fields. Errors report the record and field of the invalid
+/// quote.
+fn records(text: &str, delimiter: char) -> Result<Vec<Vec<String>>, (usize, usize)> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut was_quoted = false;
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => {
+                    quoted = false;
+                    match chars.peek() {
+                        None | Some('\n') | Some('\r') => {}
+                        Some(next) if *next == delimiter => {}
+                        Some(_) => return Err((records.len(), record.len())),
+                    }
+                }
+                c => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() && !was_quoted => {
+                quoted = true;
+                was_quoted = true;
+            }
+            '"' => return Err((records.len(), record.len())),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                if was_quoted || !field.is_empty() || !record.is_empty() {
+                    record.push(core::mem::take(&mut field));
+                }
+                was_quoted = false;
+                records.push(core::mem::take(&mut record));
+            }
+            c if c == delimiter => {
+                record.push(core::mem::take(&mut field));
+                was_quoted = false;
+            }
+            c => field.push(c),
+        }
+    }
+    if quoted {
+        return Err((records.len(), record.len()));
+    }
+    if was_quoted || !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+impl<T> HexArray<T> {
+    /// Write the HexArray as CSV, one line per x index, formatting every tile with the given
+    /// function.
+    pub fn to_csv<F>(&self, f: F) -> String
+    where
+        F: FnMut(&T) -> String,
+    {
+        self.to_delimited(',', f)
+    }
+
+    /// Write the HexArray as lines of fields separated by the given delimiter, one line per x
+    /// index, formatting every tile with the given function.
+    ///
+    /// Fields containing the delimiter, a quote or a line break are quoted as in CSV.
+    pub fn to_delimited<F>(&self, delimiter: char, mut f: F) -> String
+    where
+        F: FnMut(&T) -> String,
+    {
+        let mut result = String::new();
+        for x in 0..self.height {
+            let fields: Vec<String> = self.tiles[x * self.width..(x + 1) * self.width]
+                .iter()
+                .map(|tile| quote(&f(tile), delimiter, self.width == 1))
+                .collect();
+            result.push_str(&fields.join(&delimiter.to_string()));
+            result.push('\n');
+        }
+        result
+    }
+
+    /// Parse a HexArray with the given height and width from CSV, one line per x index, parsing
+    /// every field with the given function.
+    pub fn from_csv<F, E>(
+        text: &str,
+        height: usize,
+        width: usize,
+        f: F,
+    ) -> Result<Self, CsvError<E>>
+    where
+        F: FnMut(&str) -> Result<T, E>,
+    {
+        Self::from_delimited(text, ',', height, width, f)
+    }
+
+    /// Parse a HexArray with the given height and width from lines of fields separated by the
+    /// given delimiter, one line per x index, parsing every field with the given function.
+    pub fn from_delimited<F, E>(
+        text: &str,
+        delimiter: char,
+        height: usize,
+        width: usize,
+        mut f: F,
+    ) -> Result<Self, CsvError<E>>
+    where
+        F: FnMut(&str) -> Result<T, E>,
+    {
+        let records =
+            records(text, delimiter).map_err(|(row, column)| CsvError::Quote { row, column })?;
+        if records.len() != height {
+            return Err(CsvError::Rows {
+                expected: height,
+                found: records.len(),
+            });
+        }
+        // grow as rows are checked, since the dimensions are not trusted to fit in memory
+        let mut tiles = Vec::new();
+        for (row, record) in records.iter().enumerate() {
+            if record.len() != width {
+                return Err(CsvError::Columns {
+                    row,
+                    expected: width,
+                    found: record.len(),
+                });
+            }
+            tiles.reserve(width);
+            for (column, field) in record.iter().enumerate() {
+                let tile = f(field).map_err(|error| CsvError::Field { row, column, error })?;
+                tiles.push(tile);
+            }
+        }
+        Ok(HexArray {
+            height,
+            width,
+            tiles,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // to_csv tests
+
+    #[test]
+    fn test_to_csv() {
+        let mut hex_array = HexArray::new(2, 3, 0);
+        hex_array.set(0, 2, 5);
+        hex_array.set(1, 0, -1);
+        assert_eq!(hex_array.to_csv(|tile| tile.to_string()), "0,0,5\n-1,0,0\n");
+    }
+
+    #[test]
+    fn test_to_csv_quoting() {
+        let mut hex_array = HexArray::new(1, 3, "plain".to_string());
+        hex_array.set(0, 1, "a,b".to_string());
+        hex_array.set(0, 2, "say \"hi\"".to_string());
+        assert_eq!(
+            hex_array.to_csv(|tile| tile.clone()),
+            "plain,\"a,b\",\"say \"\"hi\"\"\"\n"
+        );
+    }
+
+    // from_csv tests
+
+    #[test]
+    fn test_from_csv() {
+        let hex_array =
+            HexArray::<i32>::from_csv("1,2\r\n3,4\r\n5,6", 3, 2, |field| field.trim().parse())
+                .unwrap();
+        assert_eq!(hex_array.get(0, 1), Some(&2));
+        assert_eq!(hex_array.get(2, 0), Some(&5));
+    }
+
+    #[test]
+    fn test_from_csv_round_trip() {
+        let mut hex_array = HexArray::new(3, 1, String::new());
+        hex_array.set(1, 0, "line\nbreak".to_string());
+        hex_array.set(2, 0, "\"".to_string());
+        let csv = hex_array.to_csv(|tile| tile.clone());
+        let parsed =
+            HexArray::from_csv(&csv, 3, 1, |field| Ok::<_, ()>(field.to_string())).unwrap();
+        assert!(parsed == hex_array);
+    }
+
+    #[test]
+    fn test_from_csv_errors() {
+        let parse = |field: &str| field.parse::<i32>();
+        assert_eq!(
+            HexArray::from_csv("1,2\n3,4\n", 3, 2, parse).unwrap_err(),
+            CsvError::Rows {
+                expected: 3,
+                found: 2
+            }
+        );
+        assert_eq!(
+            HexArray::from_csv("1,2\n3\n", 2, 2, parse).unwrap_err(),
+            CsvError::Columns {
+                row: 1,
+                expected: 2,
+                found: 1
+            }
+        );
+        assert!(matches!(
+            HexArray::from_csv("1,2\n3,x\n", 2, 2, parse),
+            Err(CsvError::Field {
+                row: 1,
+                column: 1,
+                ..
+            })
+        ));
+        assert_eq!(
+            HexArray::from_csv("1,\"2\n", 1, 2, parse).unwrap_err(),
+            CsvError::Quote { row: 0, column: 1 }
+        );
+        assert_eq!(
+            HexArray::from_csv("1\n", 1, usize::MAX, parse).unwrap_err(),
+            CsvError::Columns {
+                row: 0,
+                expected: usize::MAX,
+                found: 1
+            }
+        );
+    }
+
+    // delimited tests
+
+    #[test]
+    fn test_delimited() {
+        let mut hex_array = HexArray::new(2, 2, 'a');
+        hex_array.set(1, 1, ' ');
+        let text = hex_array.to_delimited(' ', |tile| tile.to_string());
+        assert_eq!(text, "a a\na \" \"\n");
+        let parsed =
+            HexArray::from_delimited(&text, ' ', 2, 2, |field| field.chars().next().ok_or(()))
+                .unwrap();
+        assert!(parsed == hex_array);
+    }
+}
@@ -0,0 +1,142 @@
+use crate::{from_axial, to_axial, HexArray};
+
+/// One of the six directions from a tile to an adjacent tile, in clockwise order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    North,
+    NorthEast,
+    SouthEast,
+    South,
+    SouthWest,
+    NorthWest,
+}
+
+impl Direction {
+    /// All directions in clockwise order, starting at North.
+    pub const ALL: [Direction; 6] = [
+        Direction::North,
+        Direction::NorthEast,
+        Direction::SouthEast,
+        Direction::South,
+        Direction::SouthWest,
+        Direction::NorthWest,
+    ];
+
+    /// Get the index of the direction in `Direction::ALL`.
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Get the direction with the given index in `Direction::ALL`, wrapping around.
+    pub fn from_index(index: usize) -> Self {
+        Self::ALL[index % 6]
+    }
+
+    /// Get the opposite direction.
+    pub fn opposite(self) -> Self {
+        self.rotate(3)
+    }
+
+    /// Rotate the direction clockwise by the given number of 60 degree steps.
+    pub fn rotate(self, steps: usize) -> Self {
+        Self::from_index(self.index() + steps % 6)
+    }
+
+    fn axial(self) -> (isize, isize) {
+        match self {
+            Direction::North => (0, 1),
+            Direction::NorthEast => (1, 0),
+            Direction::SouthEast => (1, -1),
+            Direction::South => (0, -1),
+            Direction::SouthWest => (-1, 0),
+            Direction::NorthWest => (-1, 1),
+        }
+    }
+
+    /// Get the, possibly out of bounds, indices of the tile in this direction.
+    pub(crate) fn step(self, x: isize, y: isize) -> (isize, isize) {
+        let (q, r) = to_axial(x, y);
+        let (dq, dr) = self.axial();
+        from_axial(q + dq, r + dr)
+    }
+}
+
+impl<T> HexArray<T> {
+    /// Get the indices of the tile in the given direction from the given indices.
+    pub fn neighbor(&self, x: usize, y: usize, direction: Direction) -> Option<(usize, usize)> {
+        let (x, y) = direction.step(x as isize, y as isize);
+        self.in_bounds(x, y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // rotate tests
+
+    #[test]
+    fn test_rotate() {
+        assert_eq!(Direction::North.rotate(1), Direction::NorthEast);
+        assert_eq!(Direction::NorthWest.rotate(1), Direction::North);
+        assert_eq!(Direction::SouthEast.rotate(9), Direction::NorthWest);
+    }
+
+    #[test]
+    fn test_opposite() {
+        for direction in Direction::ALL {
+            assert_eq!(direction.opposite().opposite(), direction);
+            assert_ne!(direction.opposite(), direction);
+        }
+        assert_eq!(Direction::NorthEast.opposite(), Direction::SouthWest);
+    }
+
+    // neighbor tests
+
+    #[test]
+    fn test_neighbor_even() {
+        let hex_array = HexArray::new(4, 4, 0);
+        assert_eq!(hex_array.neighbor(2, 1, Direction::North), Some((2, 2)));
+        assert_eq!(hex_array.neighbor(2, 1, Direction::NorthEast), Some((3, 1)));
+        assert_eq!(hex_array.neighbor(2, 1, Direction::SouthEast), Some((3, 0)));
+        assert_eq!(hex_array.neighbor(2, 1, Direction::South), Some((2, 0)));
+        assert_eq!(hex_array.neighbor(2, 1, Direction::SouthWest), Some((1, 0)));
+        assert_eq!(hex_array.neighbor(2, 1, Direction::NorthWest), Some((1, 1)));
+    }
+
+    #[test]
+    fn test_neighbor_odd() {
+        let hex_array = HexArray::new(4, 4, 0);
+        assert_eq!(hex_array.neighbor(1, 1, Direction::North), Some((1, 2)));
+        assert_eq!(hex_array.neighbor(1, 1, Direction::NorthEast), Some((2, 2)));
+        assert_eq!(hex_array.neighbor(1, 1, Direction::SouthEast), Some((2, 1)));
+        assert_eq!(hex_array.neighbor(1, 1, Direction::South), Some((1, 0)));
+        assert_eq!(hex_array.neighbor(1, 1, Direction::SouthWest), Some((0, 1)));
+        assert_eq!(hex_array.neighbor(1, 1, Direction::NorthWest), Some((0, 2)));
+    }
+
+    #[test]
+    fn test_neighbor_out_of_bounds() {
+        let hex_array = HexArray::new(4, 4, 0);
+        assert_eq!(hex_array.neighbor(0, 0, Direction::South), None);
+        assert_eq!(hex_array.neighbor(0, 0, Direction::SouthWest), None);
+        assert_eq!(hex_array.neighbor(3, 3, Direction::NorthEast), None);
+    }
+
+    #[test]
+    fn test_neighbor_matches_adjacent() {
+        let hex_array = HexArray::new(5, 3, 0);
+        for x in 0..5 {
+            for y in 0..3 {
+                let mut neighbors: Vec<_> = Direction::ALL
+                    .iter()
+                    .filter_map(|direction| hex_array.neighbor(x, y, *direction))
+                    .collect();
+                let mut adjacent = hex_array.adjacent(x, y);
+                neighbors.sort();
+                adjacent.sort();
+                assert_eq!(neighbors, adjacent);
+            }
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use crate::{HexArray, HexGrid};
+
+impl<T> HexArray<T> {
+    /// Get the number of steps from every tile to the nearest source.
+    ///
+    /// Only tiles for which `passable` returns true are traversed; sources always have a distance
+    /// of zero. Tiles that cannot reach a source are `None`.
+    pub fn distance_field<I, F>(&self, sources: I, passable: F) -> HexArray<Option<u32>>
+    where
+        I: IntoIterator<Item = (usize, usize)>,
+        F: FnMut(&T) -> bool,
+    {
+        HexGrid::distance_field(self, sources, passable)
+    }
+
+    /// Get the cost of the cheapest path from every tile to the nearest source.
+    ///
+    /// `cost` returns the cost of entering a tile, or `None` if the tile is impassable; sources
+    /// always have a distance of zero. Tiles that cannot reach a source are `None`.
+    pub fn distance_field_weighted<I, F>(&self, sources: I, cost: F) -> HexArray<Option<u32>>
+    where
+        I: IntoIterator<Item = (usize, usize)>,
+        F: FnMut(&T) -> Option<u32>,
+    {
+        HexGrid::distance_field_weighted(self, sources, cost)
+    }
+}
+
+impl HexArray<Option<u32>> {
+    /// Get the adjacent tile with the smallest distance, if it is closer than the given tile.
+    ///
+    /// Repeatedly descending a distance field leads to the nearest source.
+    pub fn descend(&self, x: usize, y: usize) -> Option<(usize, usize)> {
+        let mut best = (*self.get(x, y)?)?;
+        let mut result = None;
+        for (nx, ny) in self.adjacent(x, y) {
+            if let Some(distance) = self.tiles[nx * self.width + ny] {
+                if distance < best {
+                    best = distance;
+                    result = Some((nx, ny));
+                }
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // distance_field tests
+
+    #[test]
+    fn test_distance_field() {
+        let hex_array = HexArray::new(4, 4, true);
+        let field = hex_array.distance_field([(0, 0)], |passable| *passable);
+        assert_eq!(field.get(0, 0), Some(&Some(0)));
+        assert_eq!(field.get(1, 0), Some(&Some(1)));
+        assert_eq!(field.get(0, 1), Some(&Some(1)));
+        assert_eq!(field.get(2, 1), Some(&Some(2)));
+        assert_eq!(field.get(3, 3), Some(&Some(5)));
+    }
+
+    #[test]
+    fn test_distance_field_multiple_sources() {
+        let hex_array = HexArray::new(4, 4, true);
+        let field = hex_array.distance_field([(0, 0), (3, 3)], |passable| *passable);
+        assert_eq!(field.get(0, 0), Some(&Some(0)));
+        assert_eq!(field.get(3, 3), Some(&Some(0)));
+        assert_eq!(field.get(3, 2), Some(&Some(1)));
+        assert_eq!(field.get(0, 3), Some(&Some(3)));
+    }
+
+    #[test]
+    fn test_distance_field_impassable() {
+        let mut hex_array = HexArray::new(4, 4, true);
+        for y in 0..4 {
+            hex_array.set(1, y, false);
+        }
+        let field = hex_array.distance_field([(0, 0)], |passable| *passable);
+        assert_eq!(field.get(0, 3), Some(&Some(3)));
+        assert_eq!(field.get(1, 0), Some(&None));
+        assert_eq!(field.get(2, 0), Some(&None));
+    }
+
+    // distance_field_weighted tests
+
+    #[test]
+    fn test_distance_field_weighted() {
+        let mut hex_array = HexArray::new(1, 4, 1);
+        hex_array.set(0, 2, 5);
+        let field = hex_array.distance_field_weighted([(0, 0)], |cost| Some(*cost));
+        assert_eq!(field.get(0, 0), Some(&Some(0)));
+        assert_eq!(field.get(0, 1), Some(&Some(1)));
+        assert_eq!(field.get(0, 2), Some(&Some(6)));
+        assert_eq!(field.get(0, 3), Some(&Some(7)));
+    }
+
+    #[test]
+    fn test_distance_field_weighted_detour() {
+        let mut hex_array = HexArray::new(2, 3, 1);
+        hex_array.set(0, 1, 10);
+        let field = hex_array.distance_field_weighted([(0, 0)], |cost| Some(*cost));
+        assert_eq!(field.get(0, 1), Some(&Some(10)));
+        assert_eq!(field.get(1, 1), Some(&Some(2)));
+        assert_eq!(field.get(0, 2), Some(&Some(3)));
+    }
+
+    #[test]
+    fn test_distance_field_weighted_impassable() {
+        let mut hex_array = HexArray::new(1, 3, Some(1));
+        hex_array.set(0, 1, None);
+        let field = hex_array.distance_field_weighted([(0, 0)], |cost| *cost);
+        assert_eq!(field.get(0, 1), Some(&None));
+        assert_eq!(field.get(0, 2), Some(&None));
+    }
+
+    // descend tests
+
+    #[test]
+    fn test_descend() {
+        let hex_array = HexArray::new(4, 4, true);
+        let field = hex_array.distance_field([(0, 0)], |passable| *passable);
+        let mut tile = (3, 3);
+        let mut steps = 0;
+        while let Some(next) = field.descend(tile.0, tile.1) {
+            tile = next;
+            steps += 1;
+        }
+        assert_eq!(tile, (0, 0));
+        assert_eq!(steps, 5);
+    }
+
+    #[test]
+    fn test_descend_unreachable() {
+        let field = HexArray::new(2, 2, None);
+        assert_eq!(field.descend(0, 0), None);
+        assert_eq!(field.descend(5, 5), None);
+    }
+}
@@ -0,0 +1,307 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::{pos, Direction, HexArray, HexVertex, Point};
+
+/// An edge between two tiles.
+///
+/// Every edge has a single canonical representation: the North, NorthEast or SouthEast edge of
+/// one of its two tiles. The tile indices of an edge may be outside of any HexArray.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct HexEdge {
+    x: isize,
+    y: isize,
+    direction: Direction,
+}
+
+impl HexEdge {
+    /// Get the edge of the tile at the given indices in the given direction.
+    pub fn new(x: usize, y: usize, direction: Direction) -> Self {
+        Self::from_tile(x as isize, y as isize, direction)
+    }
+
+    pub(crate) fn from_tile(x: isize, y: isize, direction: Direction) -> Self {
+        match direction {
+            Direction::North | Direction::NorthEast | Direction::SouthEast => {
+                HexEdge { x, y, direction }
+            }
+            _ => {
+                let (x, y) = direction.step(x, y);
+                HexEdge {
+                    x,
+                    y,
+                    direction: direction.opposite(),
+                }
+            }
+        }
+    }
+
+    /// Get the x index of the tile owning the edge.
+    pub fn x(&self) -> isize {
+        self.x
+    }
+
+    /// Get the y index of the tile owning the edge.
+    pub fn y(&self) -> isize {
+        self.y
+    }
+
+    /// Get the direction of the edge from the tile owning it.
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// Get the indices of the two tiles sharing the edge.
+    pub fn tiles(&self) -> [(isize, isize); 2] {
+        [(self.x, self.y), self.direction.step(self.x, self.y)]
+    }
+
+    /// Get the two vertices at the ends of the edge.
+    pub fn vertices(&self) -> [HexVertex; 2] {
+        let corner = self.direction.index();
+        [
+            HexVertex::from_tile(self.x, self.y, corner + 5),
+            HexVertex::from_tile(self.x, self.y, corner),
+        ]
+    }
+
+    fn pos(&self) -> (f32, f32) {
+        let [(ax, ay), (bx, by)] = self.tiles();
+        let (ax, ay) = pos(ax, ay);
+        let (bx, by) = pos(bx, by);
+        ((ax + bx) / 2.0, (ay + by) / 2.0)
+    }
+
+    /// Get the position of the midpoint of the edge.
+    pub fn position(&self) -> Point {
+        self.pos().into()
+    }
+}
+
+impl<T> HexArray<T> {
+    /// Get the edges of the tile at the given indices, in the order of `Direction::ALL`.
+    pub fn edges(x: usize, y: usize) -> [HexEdge; 6] {
+        Direction::ALL.map(|direction| HexEdge::new(x, y, direction))
+    }
+
+    /// Get the indices of the tiles sharing the given edge that are inside the HexArray.
+    pub fn edge_tiles(&self, edge: HexEdge) -> Vec<(usize, usize)> {
+        edge.tiles()
+            .into_iter()
+            .filter_map(|(x, y)| self.in_bounds(x, y))
+            .collect()
+    }
+}
+
+/// Storage for a value on every edge of the tiles of a HexArray with the same dimensions.
+pub struct EdgeArray<T> {
+    height: usize,
+    width: usize,
+    edges: Vec<T>,
+}
+
+impl<T> EdgeArray<T>
+where
+    T: Clone,
+{
+    /// Create a new EdgeArray for a HexArray with the given height, width, and default value.
+    pub fn new(height: usize, width: usize, default: T) -> Self {
+        EdgeArray {
+            height,
+            width,
+            edges: vec![default; (height + 2) * (width + 2) * 3],
+        }
+    }
+}
+
+impl<T> EdgeArray<T> {
+    /// Get the height of the HexArray the EdgeArray belongs to.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Get the width of the HexArray the EdgeArray belongs to.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    fn index(&self, edge: HexEdge) -> Option<usize> {
+        let (height, width) = (self.height as isize, self.width as isize);
+        let inside = edge
+            .tiles()
+            .iter()
+            .any(|(x, y)| (0..height).contains(x) && (0..width).contains(y));
+        if inside {
+            let x = (edge.x + 1) as usize;
+            let y = (edge.y + 1) as usize;
+            Some((x * (self.width + 2) + y) * 3 + edge.direction.index())
+        } else {
+            None
+        }
+    }
+
+    /// Check whether the edge borders at least one tile of the HexArray.
+    pub fn contains(&self, edge: HexEdge) -> bool {
+        self.index(edge).is_some()
+    }
+
+    /// Get the value on the given edge.
+    pub fn get(&self, edge: HexEdge) -> Option<&T> {
+        self.index(edge).map(|index| &self.edges[index])
+    }
+
+    /// Get a mutable reference to the value on the given edge.
+    pub fn get_mut(&mut self, edge: HexEdge) -> Option<&mut T> {
+        self.index(edge).map(|index| &mut self.edges[index])
+    }
+
+    /// Set the value on the given edge.
+    pub fn set(&mut self, edge: HexEdge, value: T) -> Option<()> {
+        let index = self.index(edge)?;
+        self.edges[index] = value;
+        Some(())
+    }
+
+    /// Iterate over every edge bordering at least one tile of the HexArray.
+    pub fn edges(&self) -> impl Iterator<Item = HexEdge> + '_ {
+        (-1..=self.height as isize)
+            .flat_map(move |x| (-1..=self.width as isize).map(move |y| (x, y)))
+            .flat_map(|(x, y)| {
+                [Direction::North, Direction::NorthEast, Direction::SouthEast]
+                    .map(|direction| HexEdge { x, y, direction })
+            })
+            .filter(move |edge| self.contains(*edge))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // new tests
+
+    #[test]
+    fn test_new_canonical() {
+        assert_eq!(
+            HexEdge::new(2, 1, Direction::South),
+            HexEdge::new(2, 0, Direction::North)
+        );
+        assert_eq!(
+            HexEdge::new(2, 1, Direction::SouthWest),
+            HexEdge::new(1, 0, Direction::NorthEast)
+        );
+        assert_eq!(
+            HexEdge::new(1, 1, Direction::NorthWest),
+            HexEdge::new(0, 2, Direction::SouthEast)
+        );
+    }
+
+    #[test]
+    fn test_new_shared() {
+        let hex_array = HexArray::new(4, 4, 0);
+        for x in 0..4 {
+            for y in 0..4 {
+                for direction in Direction::ALL {
+                    let (nx, ny) = match hex_array.neighbor(x, y, direction) {
+                        Some(neighbor) => neighbor,
+                        None => continue,
+                    };
+                    assert_eq!(
+                        HexEdge::new(x, y, direction),
+                        HexEdge::new(nx, ny, direction.opposite())
+                    );
+                }
+            }
+        }
+    }
+
+    // tiles tests
+
+    #[test]
+    fn test_tiles() {
+        let edge = HexEdge::new(0, 0, Direction::SouthWest);
+        assert_eq!(edge.x(), -1);
+        assert_eq!(edge.y(), -1);
+        assert_eq!(edge.tiles(), [(-1, -1), (0, 0)]);
+    }
+
+    #[test]
+    fn test_edge_tiles() {
+        let hex_array = HexArray::new(4, 4, 0);
+        assert_eq!(
+            hex_array.edge_tiles(HexEdge::new(1, 1, Direction::North)),
+            vec![(1, 1), (1, 2)]
+        );
+        assert_eq!(
+            hex_array.edge_tiles(HexEdge::new(0, 0, Direction::South)),
+            vec![(0, 0)]
+        );
+    }
+
+    // vertices tests
+
+    #[test]
+    fn test_vertices() {
+        let [a, b] = HexEdge::new(1, 1, Direction::NorthEast).vertices();
+        assert_eq!(a, HexVertex::new(1, 1, 0));
+        assert_eq!(b, HexVertex::new(1, 1, 1));
+    }
+
+    // position tests
+
+    #[test]
+    fn test_position() {
+        let (x, y) = HexEdge::new(0, 0, Direction::North).pos();
+        assert_eq!((x, y), (0.0, 0.8660254));
+        let (x, y) = HexEdge::new(0, 0, Direction::NorthEast).pos();
+        assert_eq!((x, y), (0.75, 0.4330127));
+    }
+
+    // EdgeArray tests
+
+    #[test]
+    fn test_edge_array() {
+        let mut edge_array = EdgeArray::new(4, 4, 0);
+        let edge = HexEdge::new(1, 1, Direction::South);
+        assert_eq!(edge_array.set(edge, 1), Some(()));
+        assert_eq!(
+            edge_array.get(HexEdge::new(1, 0, Direction::North)),
+            Some(&1)
+        );
+        *edge_array.get_mut(edge).unwrap() += 1;
+        assert_eq!(edge_array.get(edge), Some(&2));
+        assert_eq!(
+            edge_array.get(HexEdge::new(1, 0, Direction::NorthEast)),
+            Some(&0)
+        );
+    }
+
+    #[test]
+    fn test_edge_array_border() {
+        let edge_array = EdgeArray::new(4, 4, 0);
+        assert!(edge_array.contains(HexEdge::new(0, 0, Direction::SouthWest)));
+        assert!(edge_array.contains(HexEdge::new(3, 3, Direction::NorthEast)));
+        assert!(!edge_array.contains(HexEdge::new(5, 5, Direction::North)));
+        assert_eq!(edge_array.get(HexEdge::new(8, 0, Direction::North)), None);
+    }
+
+    #[test]
+    fn test_edge_array_edges() {
+        let edge_array = EdgeArray::new(2, 3, 0);
+        let edges: Vec<_> = edge_array.edges().collect();
+        let mut expected = Vec::new();
+        for x in 0..2 {
+            for y in 0..3 {
+                for edge in HexArray::<i32>::edges(x, y) {
+                    if !expected.contains(&edge) {
+                        expected.push(edge);
+                    }
+                }
+            }
+        }
+        assert_eq!(edges.len(), expected.len());
+        for edge in expected {
+            assert!(edges.contains(&edge));
+        }
+    }
+}
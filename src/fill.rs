@@ -0,0 +1,310 @@
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+
+use crate::{
+    axial_range, from_axial, hex_distance, max_distance_within, pos_f64, pos_to_tile, to_axial,
+    Direction, HexArray, HexBitArray, Point,
+};
+
+/// Check whether the point lies inside the polygon, using the even-odd rule.
+fn polygon_contains(polygon: &[Point<f64>], x: f64, y: f64) -> bool {
+    let mut inside = false;
+    let mut previous = polygon[polygon.len() - 1];
+    for &point in polygon {
+        if (point.y > y) != (previous.y > y)
+            && x < (previous.x - point.x) * (y - point.y) / (previous.y - point.y) + point.x
+        {
+            inside = !inside;
+        }
+        previous = point;
+    }
+    inside
+}
+
+impl<T> HexArray<T>
+where
+    T: Clone + PartialEq,
+{
+    /// Set the tile at the given, possibly out of bounds, indices, returning whether it changed.
+    fn fill_tile(&mut self, x: isize, y: isize, value: &T) -> bool {
+        match self.in_bounds(x, y) {
+            Some((x, y)) => {
+                let tile = &mut self.tiles[x * self.width + y];
+                let changed = tile != value;
+                if changed {
+                    *tile = value.clone();
+                }
+                changed
+            }
+            None => false,
+        }
+    }
+
+    /// Set every tile at the given indices to the given value, ignoring indices outside the
+    /// HexArray.
+    ///
+    /// Returns the number of tiles whose value changed.
+    pub fn fill<I>(&mut self, tiles: I, value: T) -> usize
+    where
+        I: IntoIterator<Item = (usize, usize)>,
+    {
+        let mut changed = 0;
+        for (x, y) in tiles {
+            if x < self.height && y < self.width {
+                changed += self.fill_tile(x as isize, y as isize, &value) as usize;
+            }
+        }
+        changed
+    }
+
+    /// Set every tile within the given distance of the center to the given value.
+    ///
+    /// Returns the number of tiles whose value changed.
+    pub fn fill_range(&mut self, center: (usize, usize), radius: usize, value: T) -> usize {
+        let center = (center.0 as isize, center.1 as isize);
+        let radius = radius.min(max_distance_within(center, self.height, self.width));
+        let (q, r) = to_axial(center.0, center.1);
+        let mut changed = 0;
+        for (dq, dr, _) in axial_range(radius) {
+            let (x, y) = from_axial(q + dq, r + dr);
+            changed += self.fill_tile(x, y, &value) as usize;
+        }
+        changed
+    }
+
+    /// Set every tile at exactly the given distance from the center to the given value.
+    ///
+    /// Returns the number of tiles whose value changed.
+    pub fn fill_ring(&mut self, center: (usize, usize), radius: usize, value: T) -> usize {
+        let (mut x, mut y) = (center.0 as isize, center.1 as isize);
+        if radius == 0 {
+            return self.fill_tile(x, y, &value) as usize;
+        }
+        if radius > max_distance_within((x, y), self.height, self.width) {
+            return 0;
+        }
+        for _ in 0..radius {
+            (x, y) = Direction::SouthWest.step(x, y);
+        }
+        let mut changed = 0;
+        for direction in Direction::ALL {
+            for _ in 0..radius {
+                changed += self.fill_tile(x, y, &value) as usize;
+                (x, y) = direction.step(x, y);
+            }
+        }
+        changed
+    }
+
+    /// Set every tile on the straight line between two tiles, including both ends, to the given
+    /// value.
+    ///
+    /// Returns the number of tiles whose value changed.
+    pub fn fill_line(&mut self, start: (usize, usize), end: (usize, usize), value: T) -> usize {
+        let (start, end) = (
+            (start.0 as isize, start.1 as isize),
+            (end.0 as isize, end.1 as isize),
+        );
+        let steps = hex_distance(start, end);
+        let (sx, sy) = pos_f64(start.0, start.1);
+        let (ex, ey) = pos_f64(end.0, end.1);
+        let mut changed = 0;
+        for step in 0..=steps {
+            let t = if steps == 0 {
+                0.0
+            } else {
+                step as f64 / steps as f64
+            };
+            // nudge off tile edges so that ties always round the same way
+            let (x, y) = pos_to_tile(sx + (ex - sx) * t + 1e-6, sy + (ey - sy) * t + 2e-6);
+            changed += self.fill_tile(x, y, &value) as usize;
+        }
+        changed
+    }
+
+    /// Set every tile whose position lies inside the polygon to the given value.
+    ///
+    /// Returns the number of tiles whose value changed.
+    pub fn fill_polygon(&mut self, polygon: &[Point], value: T) -> usize {
+        if polygon.is_empty() {
+            return 0;
+        }
+        const SQRT_3: f64 = 1.732050807568877293527446341505872367_f64;
+        let polygon: Vec<Point<f64>> = polygon.iter().map(|&p| p.into()).collect();
+        let (mut left, mut right, mut bottom, mut top) = (f64::MAX, f64::MIN, f64::MAX, f64::MIN);
+        for point in &polygon {
+            left = left.min(point.x);
+            right = right.max(point.x);
+            bottom = bottom.min(point.y);
+            top = top.max(point.y);
+        }
+        // huge or infinite vertices saturate the conversions, so the offsets must saturate too
+        let xs = ((left / 1.5) as isize).saturating_sub(1).max(0)
+            ..((right / 1.5) as isize).saturating_add(2);
+        let ys = ((bottom / SQRT_3) as isize).saturating_sub(1).max(0)
+            ..((top / SQRT_3) as isize).saturating_add(2);
+        let mut changed = 0;
+        for x in xs.start..xs.end.min(self.height as isize) {
+            for y in ys.start..ys.end.min(self.width as isize) {
+                let (px, py) = pos_f64(x, y);
+                if polygon_contains(&polygon, px, py) {
+                    changed += self.fill_tile(x, y, &value) as usize;
+                }
+            }
+        }
+        changed
+    }
+
+    /// Set every tile connected to the start tile through tiles matching the predicate to the
+    /// given value, including the start tile if it matches.
+    ///
+    /// Returns the number of tiles whose value changed.
+    pub fn fill_region<F>(&mut self, start: (usize, usize), mut predicate: F, value: T) -> usize
+    where
+        F: FnMut(&T) -> bool,
+    {
+        if !self.get(start.0, start.1).is_some_and(&mut predicate) {
+            return 0;
+        }
+        let mut visited = HexBitArray::new(self.height, self.width, false);
+        visited.set(start.0, start.1, true);
+        let mut queue = VecDeque::from([start]);
+        let mut changed = 0;
+        while let Some((x, y)) = queue.pop_front() {
+            for (nx, ny) in self.adjacent(x, y) {
+                if visited.get(nx, ny) == Some(false)
+                    && predicate(&self.tiles[nx * self.width + ny])
+                {
+                    visited.set(nx, ny, true);
+                    queue.push_back((nx, ny));
+                }
+            }
+            changed += self.fill_tile(x as isize, y as isize, &value) as usize;
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(hex_array: &HexArray<u8>, value: u8) -> usize {
+        hex_array.fold(0, |count, tile| count + (*tile == value) as usize)
+    }
+
+    // fill tests
+
+    #[test]
+    fn test_fill() {
+        let mut hex_array = HexArray::new(3, 3, 0u8);
+        assert_eq!(hex_array.fill([(0, 0), (1, 2), (0, 0), (3, 0)], 1), 2);
+        assert_eq!(hex_array.fill([(0, 0), (2, 2)], 1), 1);
+        assert_eq!(count(&hex_array, 1), 3);
+    }
+
+    // fill_range tests
+
+    #[test]
+    fn test_fill_range() {
+        let mut hex_array = HexArray::new(9, 9, 0u8);
+        assert_eq!(hex_array.fill_range((4, 4), 2, 1), 19);
+        assert_eq!(hex_array.fill_range((4, 4), 3, 1), 18);
+        assert_eq!(hex_array.fill_range((0, 0), 1, 2), 3);
+        assert_eq!(hex_array.get(4, 7), Some(&1));
+        assert_eq!(hex_array.get(4, 8), Some(&0));
+        let mut small = HexArray::new(4, 4, 0u8);
+        assert_eq!(small.fill_range((1, 1), usize::MAX, 1), 16);
+        assert_eq!(small.fill_range((1, 1), 1_000_000, 2), 16);
+        assert_eq!(small.fill_range((20, 0), 20, 3), 16);
+        let mut outside = HexArray::new(4, 4, 0u8);
+        assert_eq!(outside.fill_range((20, 0), 17, 1), 4);
+        assert!((0..4).all(|y| outside.get(3, y) == Some(&1)));
+    }
+
+    // fill_ring tests
+
+    #[test]
+    fn test_fill_ring() {
+        let mut hex_array = HexArray::new(9, 9, 0u8);
+        assert_eq!(hex_array.fill_ring((4, 4), 0, 1), 1);
+        assert_eq!(hex_array.fill_ring((4, 4), 2, 1), 12);
+        let distances = hex_array.distance_field([(4, 4)], |_| true);
+        for x in 0..9 {
+            for y in 0..9 {
+                let expected = matches!(distances.get(x, y), Some(Some(0 | 2)));
+                assert_eq!(hex_array.get(x, y) == Some(&1), expected, "({x}, {y})");
+            }
+        }
+        assert_eq!(hex_array.fill_ring((0, 0), 2, 2), 4);
+        assert_eq!(hex_array.fill_ring((0, 0), usize::MAX, 3), 0);
+        assert_eq!(hex_array.fill_ring((0, 0), 18, 3), 0);
+        let mut outside = HexArray::new(4, 4, 0u8);
+        assert_eq!(outside.fill_ring((20, 0), 17, 1), 4);
+        assert!((0..4).all(|y| outside.get(3, y) == Some(&1)));
+        assert_eq!(outside.fill_ring((20, 0), 25, 1), 0);
+    }
+
+    // fill_line tests
+
+    #[test]
+    fn test_fill_line() {
+        let mut hex_array = HexArray::new(6, 6, 0u8);
+        assert_eq!(hex_array.fill_line((0, 0), (5, 2), 1), 6);
+        let distances = hex_array.distance_field([(0, 0)], |tile| *tile == 1);
+        assert_eq!(distances.get(5, 2), Some(&Some(5)));
+        assert_eq!(hex_array.fill_line((2, 2), (2, 2), 2), 1);
+        assert_eq!(hex_array.fill_line((0, 5), (0, 3), 3), 3);
+        assert_eq!(hex_array.get(0, 4), Some(&3));
+    }
+
+    // fill_polygon tests
+
+    #[test]
+    fn test_fill_polygon() {
+        let mut hex_array = HexArray::new(6, 6, 0u8);
+        let square = [
+            Point::new(1.0, 1.0),
+            Point::new(5.0, 1.0),
+            Point::new(5.0, 5.0),
+            Point::new(1.0, 5.0),
+        ];
+        let changed = hex_array.fill_polygon(&square, 1);
+        assert_eq!(changed, count(&hex_array, 1));
+        for x in 0..6 {
+            for y in 0..6 {
+                let position = HexArray::<u8>::position(x, y);
+                let inside = (1.0..5.0).contains(&position.x) && (1.0..5.0).contains(&position.y);
+                assert_eq!(hex_array.get(x, y) == Some(&1), inside, "({x}, {y})");
+            }
+        }
+        assert_eq!(hex_array.fill_polygon(&[], 1), 0);
+        let huge = [
+            Point::new(-1.0, -1.0),
+            Point::new(f32::MAX, -1.0),
+            Point::new(-1.0, f32::MAX),
+        ];
+        assert_eq!(hex_array.fill_polygon(&huge, 2), 36);
+        let infinite = [
+            Point::new(-1.0, -1.0),
+            Point::new(f32::INFINITY, -1.0),
+            Point::new(-1.0, 10.0),
+        ];
+        let changed = hex_array.fill_polygon(&infinite, 3);
+        assert_eq!(changed, count(&hex_array, 3));
+    }
+
+    // fill_region tests
+
+    #[test]
+    fn test_fill_region() {
+        let mut hex_array = HexArray::new(4, 4, 0u8);
+        for y in 0..4 {
+            hex_array.set(1, y, 1);
+        }
+        assert_eq!(hex_array.fill_region((0, 0), |tile| *tile == 0, 2), 4);
+        assert_eq!(count(&hex_array, 2), 4);
+        assert_eq!(hex_array.fill_region((3, 3), |tile| *tile != 1, 2), 8);
+        assert_eq!(hex_array.fill_region((1, 0), |tile| *tile == 0, 3), 0);
+    }
+}
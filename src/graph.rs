@@ -0,0 +1,525 @@
+//! Run petgraph algorithms directly on a HexArray.
+//!
+//! `HexGraph` is a directed graph whose nodes are the tiles of a HexArray, identified by their
+//! indices, with an edge from every tile to each adjacent tile that the weight function accepts.
+//! Edge weights are computed by the weight function whenever an edge is visited, so they must be
+//! `Copy`; node weights are borrowed from the HexArray. The visitor traits are implemented for
+//! `&HexGraph`, and none of their iterators allocate. Only `DataMap`, which lends out edge weights,
+//! stores them, in a cache allocated on its first use.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cell::OnceCell;
+use core::marker::PhantomData;
+use core::ops::Range;
+
+use petgraph::data::DataMap;
+use petgraph::visit::{
+    Data, EdgeCount, EdgeIndexable, EdgeRef, GraphBase, GraphProp, IntoEdgeReferences, IntoEdges,
+    IntoEdgesDirected, IntoNeighbors, IntoNeighborsDirected, IntoNodeIdentifiers,
+    IntoNodeReferences, NodeCompactIndexable, NodeCount, NodeIndexable, VisitMap, Visitable,
+};
+use petgraph::{Directed, Direction as EdgeDirection};
+
+use crate::{Direction, HexArray};
+
+/// A graph view of a HexArray, with an edge weight for every pair of adjacent tiles.
+pub struct HexGraph<'a, T, W, F> {
+    hex_array: &'a HexArray<T>,
+    weight: F,
+    /// The weights lent out by `DataMap::edge_weight`, indexed by edge id.
+    weights: OnceCell<Vec<OnceCell<Option<W>>>>,
+    marker: PhantomData<fn() -> W>,
+}
+
+impl<'a, T, W, F> HexGraph<'a, T, W, F>
+where
+    F: Fn(&T, &T) -> Option<W>,
+{
+    /// Create a graph of the HexArray, with an edge from a tile to an adjacent tile wherever the
+    /// weight function, given both tiles in that order, returns a weight.
+    pub fn new(hex_array: &'a HexArray<T>, weight: F) -> Self {
+        HexGraph {
+            hex_array,
+            weight,
+            weights: OnceCell::new(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Get the HexArray of the graph.
+    pub fn hex_array(&self) -> &'a HexArray<T> {
+        self.hex_array
+    }
+
+    fn node_index(&self, (x, y): (usize, usize)) -> usize {
+        x * self.hex_array.width + y
+    }
+
+    fn node_id(&self, index: usize) -> (usize, usize) {
+        (index / self.hex_array.width, index % self.hex_array.width)
+    }
+
+    /// Get the edge from the given tile in the given direction, if any.
+    fn edge(&self, source: (usize, usize), direction: Direction) -> Option<HexGraphEdge<W>> {
+        let target = self.hex_array.neighbor(source.0, source.1, direction)?;
+        let tiles = &self.hex_array.tiles;
+        let weight = (self.weight)(
+            &tiles[self.node_index(source)],
+            &tiles[self.node_index(target)],
+        )?;
+        Some(HexGraphEdge {
+            source,
+            target,
+            id: self.node_index(source) * 6 + direction.index(),
+            weight,
+        })
+    }
+
+    /// Get the node indices of the given tile, which are empty if it lies outside the HexArray.
+    fn node_range(&self, (x, y): (usize, usize)) -> Range<usize> {
+        if x < self.hex_array.height && y < self.hex_array.width {
+            let index = self.node_index((x, y));
+            index..index + 1
+        } else {
+            0..0
+        }
+    }
+
+    /// Iterate over the edges from or to the tiles with the given node indices.
+    fn edges_of(&self, nodes: Range<usize>, incoming: bool) -> Edges<'_, 'a, T, W, F> {
+        Edges {
+            graph: self,
+            nodes,
+            direction: 0,
+            incoming,
+        }
+    }
+}
+
+/// An edge of a HexGraph.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HexGraphEdge<W> {
+    source: (usize, usize),
+    target: (usize, usize),
+    id: usize,
+    weight: W,
+}
+
+impl<W> EdgeRef for HexGraphEdge<W>
+where
+    W: Copy,
+{
+    type NodeId = (usize, usize);
+    type EdgeId = usize;
+    type Weight = W;
+
+    fn source(&self) -> (usize, usize) {
+        self.source
+    }
+
+    fn target(&self) -> (usize, usize) {
+        self.target
+    }
+
+    fn weight(&self) -> &W {
+        &self.weight
+    }
+
+    fn id(&self) -> usize {
+        self.id
+    }
+}
+
+/// An iterator over edges of a HexGraph.
+pub struct Edges<'b, 'a, T, W, F> {
+    graph: &'b HexGraph<'a, T, W, F>,
+    nodes: Range<usize>,
+    direction: usize,
+    incoming: bool,
+}
+
+impl<T, W, F> Iterator for Edges<'_, '_, T, W, F>
+where
+    F: Fn(&T, &T) -> Option<W>,
+{
+    type Item = HexGraphEdge<W>;
+
+    fn next(&mut self) -> Option<HexGraphEdge<W>> {
+        while !self.nodes.is_empty() {
+            if self.direction == 6 {
+                self.nodes.start += 1;
+                self.direction = 0;
+                continue;
+            }
+            let direction = Direction::ALL[self.direction];
+            self.direction += 1;
+            let (x, y) = self.graph.node_id(self.nodes.start);
+            let edge = if self.incoming {
+                self.graph
+                    .hex_array
+                    .neighbor(x, y, direction)
+                    .and_then(|source| self.graph.edge(source, direction.opposite()))
+            } else {
+                self.graph.edge((x, y), direction)
+            };
+            if edge.is_some() {
+                return edge;
+            }
+        }
+        None
+    }
+}
+
+/// An iterator over the tiles adjacent to a tile of a HexGraph through an edge.
+pub struct Neighbors<'b, 'a, T, W, F> {
+    edges: Edges<'b, 'a, T, W, F>,
+}
+
+impl<T, W, F> Iterator for Neighbors<'_, '_, T, W, F>
+where
+    F: Fn(&T, &T) -> Option<W>,
+{
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<(usize, usize)> {
+        let edge = self.edges.next()?;
+        Some(match self.edges.incoming {
+            true => edge.source,
+            false => edge.target,
+        })
+    }
+}
+
+/// An iterator over the indices of the tiles of a HexGraph.
+#[derive(Clone)]
+pub struct NodeIdentifiers {
+    nodes: Range<usize>,
+    width: usize,
+}
+
+impl Iterator for NodeIdentifiers {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<(usize, usize)> {
+        let index = self.nodes.next()?;
+        Some((index / self.width, index % self.width))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.nodes.size_hint()
+    }
+}
+
+/// The set of visited tiles of a HexGraph.
+pub struct VisitedTiles {
+    width: usize,
+    visited: Vec<bool>,
+}
+
+impl VisitMap<(usize, usize)> for VisitedTiles {
+    fn visit(&mut self, (x, y): (usize, usize)) -> bool {
+        !core::mem::replace(&mut self.visited[x * self.width + y], true)
+    }
+
+    fn is_visited(&self, (x, y): &(usize, usize)) -> bool {
+        self.visited[x * self.width + y]
+    }
+
+    fn unvisit(&mut self, (x, y): (usize, usize)) -> bool {
+        core::mem::replace(&mut self.visited[x * self.width + y], false)
+    }
+}
+
+impl<T, W, F> GraphBase for HexGraph<'_, T, W, F> {
+    type NodeId = (usize, usize);
+    type EdgeId = usize;
+}
+
+impl<T, W, F> Data for HexGraph<'_, T, W, F> {
+    type NodeWeight = T;
+    type EdgeWeight = W;
+}
+
+impl<T, W, F> DataMap for HexGraph<'_, T, W, F>
+where
+    F: Fn(&T, &T) -> Option<W>,
+{
+    fn node_weight(&self, (x, y): (usize, usize)) -> Option<&T> {
+        self.hex_array.get(x, y)
+    }
+
+    fn edge_weight(&self, id: usize) -> Option<&W> {
+        let weights = self
+            .weights
+            .get_or_init(|| (0..self.edge_bound()).map(|_| OnceCell::new()).collect());
+        weights
+            .get(id)?
+            .get_or_init(|| {
+                let source = self.node_id(id / 6);
+                let edge = self.edge(source, Direction::ALL[id % 6])?;
+                Some(edge.weight)
+            })
+            .as_ref()
+    }
+}
+
+impl<T, W, F> GraphProp for HexGraph<'_, T, W, F> {
+    type EdgeType = Directed;
+}
+
+impl<T, W, F> NodeCount for HexGraph<'_, T, W, F> {
+    fn node_count(&self) -> usize {
+        self.hex_array.tiles.len()
+    }
+}
+
+impl<T, W, F> NodeIndexable for HexGraph<'_, T, W, F>
+where
+    F: Fn(&T, &T) -> Option<W>,
+{
+    fn node_bound(&self) -> usize {
+        self.hex_array.tiles.len()
+    }
+
+    fn to_index(&self, node: (usize, usize)) -> usize {
+        self.node_index(node)
+    }
+
+    fn from_index(&self, index: usize) -> (usize, usize) {
+        self.node_id(index)
+    }
+}
+
+impl<T, W, F> NodeCompactIndexable for HexGraph<'_, T, W, F> where F: Fn(&T, &T) -> Option<W> {}
+
+impl<T, W, F> EdgeCount for HexGraph<'_, T, W, F>
+where
+    F: Fn(&T, &T) -> Option<W>,
+{
+    /// Count the edges, calling the weight function for every pair of adjacent tiles.
+    fn edge_count(&self) -> usize {
+        self.edges_of(0..self.hex_array.tiles.len(), false).count()
+    }
+}
+
+impl<T, W, F> EdgeIndexable for HexGraph<'_, T, W, F> {
+    fn edge_bound(&self) -> usize {
+        self.hex_array.tiles.len() * 6
+    }
+
+    fn to_index(&self, edge: usize) -> usize {
+        edge
+    }
+
+    fn from_index(&self, index: usize) -> usize {
+        index
+    }
+}
+
+impl<T, W, F> Visitable for HexGraph<'_, T, W, F> {
+    type Map = VisitedTiles;
+
+    fn visit_map(&self) -> VisitedTiles {
+        VisitedTiles {
+            width: self.hex_array.width,
+            visited: vec![false; self.hex_array.tiles.len()],
+        }
+    }
+
+    fn reset_map(&self, map: &mut VisitedTiles) {
+        map.width = self.hex_array.width;
+        map.visited.clear();
+        map.visited.resize(self.hex_array.tiles.len(), false);
+    }
+}
+
+impl<T, W, F> IntoNodeIdentifiers for &HexGraph<'_, T, W, F>
+where
+    F: Fn(&T, &T) -> Option<W>,
+{
+    type NodeIdentifiers = NodeIdentifiers;
+
+    fn node_identifiers(self) -> NodeIdentifiers {
+        NodeIdentifiers {
+            nodes: 0..self.hex_array.tiles.len(),
+            width: self.hex_array.width,
+        }
+    }
+}
+
+impl<'b, T, W, F> IntoNodeReferences for &'b HexGraph<'_, T, W, F>
+where
+    F: Fn(&T, &T) -> Option<W>,
+{
+    type NodeRef = ((usize, usize), &'b T);
+    type NodeReferences = core::iter::Zip<NodeIdentifiers, core::slice::Iter<'b, T>>;
+
+    fn node_references(self) -> Self::NodeReferences {
+        self.node_identifiers().zip(self.hex_array.tiles.iter())
+    }
+}
+
+impl<'b, 'a, T, W, F> IntoNeighbors for &'b HexGraph<'a, T, W, F>
+where
+    F: Fn(&T, &T) -> Option<W>,
+{
+    type Neighbors = Neighbors<'b, 'a, T, W, F>;
+
+    fn neighbors(self, node: (usize, usize)) -> Self::Neighbors {
+        self.neighbors_directed(node, EdgeDirection::Outgoing)
+    }
+}
+
+impl<'b, 'a, T, W, F> IntoNeighborsDirected for &'b HexGraph<'a, T, W, F>
+where
+    F: Fn(&T, &T) -> Option<W>,
+{
+    type NeighborsDirected = Neighbors<'b, 'a, T, W, F>;
+
+    fn neighbors_directed(
+        self,
+        node: (usize, usize),
+        direction: EdgeDirection,
+    ) -> Self::NeighborsDirected {
+        Neighbors {
+            edges: self.edges_of(self.node_range(node), direction == EdgeDirection::Incoming),
+        }
+    }
+}
+
+impl<'b, 'a, T, W, F> IntoEdgeReferences for &'b HexGraph<'a, T, W, F>
+where
+    W: Copy,
+    F: Fn(&T, &T) -> Option<W>,
+{
+    type EdgeRef = HexGraphEdge<W>;
+    type EdgeReferences = Edges<'b, 'a, T, W, F>;
+
+    fn edge_references(self) -> Self::EdgeReferences {
+        self.edges_of(0..self.hex_array.tiles.len(), false)
+    }
+}
+
+impl<'b, 'a, T, W, F> IntoEdges for &'b HexGraph<'a, T, W, F>
+where
+    W: Copy,
+    F: Fn(&T, &T) -> Option<W>,
+{
+    type Edges = Edges<'b, 'a, T, W, F>;
+
+    fn edges(self, node: (usize, usize)) -> Self::Edges {
+        self.edges_directed(node, EdgeDirection::Outgoing)
+    }
+}
+
+impl<'b, 'a, T, W, F> IntoEdgesDirected for &'b HexGraph<'a, T, W, F>
+where
+    W: Copy,
+    F: Fn(&T, &T) -> Option<W>,
+{
+    type EdgesDirected = Edges<'b, 'a, T, W, F>;
+
+    fn edges_directed(self, node: (usize, usize), direction: EdgeDirection) -> Self::EdgesDirected {
+        self.edges_of(self.node_range(node), direction == EdgeDirection::Incoming)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use petgraph::algo::{dijkstra, ford_fulkerson, min_spanning_tree};
+    use petgraph::data::Element;
+
+    // HexGraph tests
+
+    #[test]
+    fn test_edges() {
+        let hex_array = HexArray::new(3, 3, 1u32);
+        let graph = HexGraph::new(&hex_array, |_, _| Some(1u32));
+        assert_eq!(graph.node_count(), 9);
+        assert_eq!((&graph).neighbors((1, 1)).count(), 6);
+        assert_eq!((&graph).neighbors((0, 0)).count(), 2);
+        let adjacent: usize = (0..3)
+            .flat_map(|x| (0..3).map(move |y| (x, y)))
+            .map(|(x, y)| hex_array.adjacent(x, y).len())
+            .sum();
+        assert_eq!(graph.edge_count(), adjacent);
+        assert_eq!((&graph).edge_references().count(), adjacent);
+    }
+
+    #[test]
+    fn test_directed_weights() {
+        let mut hex_array = HexArray::new(2, 1, 0u32);
+        hex_array.set(1, 0, 5);
+        // entering a tile costs its value, zero is impassable
+        let graph = HexGraph::new(&hex_array, |_, to| (*to > 0).then_some(*to));
+        assert_eq!((&graph).neighbors((0, 0)).collect::<Vec<_>>(), vec![(1, 0)]);
+        assert_eq!((&graph).neighbors((1, 0)).count(), 0);
+        let incoming: Vec<_> = (&graph)
+            .edges_directed((1, 0), EdgeDirection::Incoming)
+            .map(|edge| (edge.source(), *edge.weight()))
+            .collect();
+        assert_eq!(incoming, vec![((0, 0), 5)]);
+    }
+
+    #[test]
+    fn test_outside_nodes() {
+        let hex_array = HexArray::new(3, 3, 1u32);
+        let graph = HexGraph::new(&hex_array, |_, _| Some(1u32));
+        assert_eq!((&graph).neighbors((0, 4)).count(), 0);
+        assert_eq!((&graph).neighbors((5, 0)).count(), 0);
+        assert_eq!((&graph).edges((0, 3)).count(), 0);
+        let incoming = (&graph).edges_directed((usize::MAX, 0), EdgeDirection::Incoming);
+        assert_eq!(incoming.count(), 0);
+    }
+
+    // algorithm tests
+
+    #[test]
+    fn test_dijkstra() {
+        let mut hex_array = HexArray::new(4, 4, 1u32);
+        hex_array.set(1, 1, 10);
+        let graph = HexGraph::new(&hex_array, |_, to| Some(*to));
+        let costs = dijkstra(&graph, (0, 0), None, |edge| *edge.weight());
+        assert_eq!(costs[&(0, 0)], 0);
+        assert_eq!(costs[&(1, 1)], 11);
+        assert_eq!(costs[&(3, 3)], 5);
+    }
+
+    #[test]
+    fn test_min_spanning_tree() {
+        let hex_array = HexArray::new(3, 4, 0u32);
+        let graph = HexGraph::new(&hex_array, |_, _| Some(1u32));
+        let edges = min_spanning_tree(&graph)
+            .filter(|element| matches!(element, Element::Edge { .. }))
+            .count();
+        assert_eq!(edges, 11);
+    }
+
+    #[test]
+    fn test_lazy_weights() {
+        let calls = core::cell::Cell::new(0);
+        let hex_array = HexArray::new(3, 3, 1u32);
+        let graph = HexGraph::new(&hex_array, |_, to| {
+            calls.set(calls.get() + 1);
+            Some(*to)
+        });
+        assert_eq!(calls.get(), 0);
+        assert_eq!((&graph).edges((1, 1)).count(), 6);
+        assert_eq!(calls.get(), 6);
+        let id = (&graph).edges((0, 0)).next().unwrap().id();
+        assert_eq!(graph.edge_weight(id), Some(&1));
+        assert_eq!(graph.edge_weight(id), Some(&1));
+        assert_eq!(calls.get(), 8);
+        assert_eq!(graph.edge_weight(usize::MAX), None);
+    }
+
+    #[test]
+    fn test_max_flow() {
+        let hex_array = HexArray::new(3, 1, ());
+        let graph = HexGraph::new(&hex_array, |_, _| Some(2u32));
+        let (flow, _) = ford_fulkerson(&graph, (0, 0), (2, 0));
+        assert_eq!(flow, 2);
+    }
+}
@@ -0,0 +1,261 @@
+use alloc::collections::{BinaryHeap, VecDeque};
+use core::cmp::Reverse;
+
+use crate::{ConstHexArray, Direction, HexArray};
+
+/// A hexagonal grid of tiles with the layout and indexing of `HexArray`.
+///
+/// Implement the required methods for a storage backend to use the algorithms provided by the
+/// trait, or write algorithms against the trait to run them on every backend. Backends may leave
+/// tiles within their height and width empty by overriding `contains`.
+pub trait HexGrid {
+    /// The value stored on every tile.
+    type Tile;
+
+    /// Get the height of the grid. Every tile has an x index below the height.
+    fn height(&self) -> usize;
+
+    /// Get the width of the grid. Every tile has a y index below the width.
+    fn width(&self) -> usize;
+
+    /// Get the value at the given indices.
+    fn get(&self, x: usize, y: usize) -> Option<&Self::Tile>;
+
+    /// Get a mutable reference to the value at the given indices.
+    fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut Self::Tile>;
+
+    /// Check whether the grid has a tile at the given indices.
+    fn contains(&self, x: usize, y: usize) -> bool {
+        x < self.height() && y < self.width()
+    }
+
+    /// Get the indices of the adjacent tile in the given direction.
+    fn neighbor(&self, x: usize, y: usize, direction: Direction) -> Option<(usize, usize)> {
+        let (x, y) = direction.step(x as isize, y as isize);
+        let (x, y) = (usize::try_from(x).ok()?, usize::try_from(y).ok()?);
+        self.contains(x, y).then_some((x, y))
+    }
+
+    /// Iterate over the indices of the tiles adjacent to the given indices, in the order of
+    /// `Direction::ALL`.
+    fn neighbors(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |direction| self.neighbor(x, y, direction))
+    }
+
+    /// Iterate over the indices of every tile, in index order.
+    fn indices(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..self.height())
+            .flat_map(move |x| (0..self.width()).map(move |y| (x, y)))
+            .filter(move |(x, y)| self.contains(*x, *y))
+    }
+
+    /// Get the number of steps from every tile to the nearest source.
+    ///
+    /// Only tiles for which `passable` returns true are traversed; sources always have a distance
+    /// of zero. Tiles that cannot reach a source are `None`.
+    fn distance_field<I, F>(&self, sources: I, mut passable: F) -> HexArray<Option<u32>>
+    where
+        I: IntoIterator<Item = (usize, usize)>,
+        F: FnMut(&Self::Tile) -> bool,
+    {
+        let width = self.width();
+        let mut result = HexArray::new(self.height(), width, None);
+        let mut queue = VecDeque::new();
+        for (x, y) in sources {
+            if !self.contains(x, y) {
+                continue;
+            }
+            if let Some(distance @ None) = result.get_mut(x, y) {
+                *distance = Some(0);
+                queue.push_back((x, y));
+            }
+        }
+        while let Some((x, y)) = queue.pop_front() {
+            let distance = result.tiles[x * width + y].unwrap();
+            for (nx, ny) in self.neighbors(x, y) {
+                let index = nx * width + ny;
+                if result.tiles[index].is_none() && self.get(nx, ny).is_some_and(&mut passable) {
+                    result.tiles[index] = Some(distance + 1);
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+        result
+    }
+
+    /// Get the cost of the cheapest path from every tile to the nearest source.
+    ///
+    /// `cost` returns the cost of entering a tile, or `None` if the tile is impassable; sources
+    /// always have a distance of zero. Tiles that cannot reach a source are `None`.
+    fn distance_field_weighted<I, F>(&self, sources: I, mut cost: F) -> HexArray<Option<u32>>
+    where
+        I: IntoIterator<Item = (usize, usize)>,
+        F: FnMut(&Self::Tile) -> Option<u32>,
+    {
+        let width = self.width();
+        let mut result = HexArray::new(self.height(), width, None);
+        let mut heap = BinaryHeap::new();
+        for (x, y) in sources {
+            if !self.contains(x, y) {
+                continue;
+            }
+            if let Some(distance) = result.get_mut(x, y) {
+                *distance = Some(0);
+                heap.push(Reverse((0u32, x, y)));
+            }
+        }
+        while let Some(Reverse((distance, x, y))) = heap.pop() {
+            if result.tiles[x * width + y] != Some(distance) {
+                continue;
+            }
+            for (nx, ny) in self.neighbors(x, y) {
+                let index = nx * width + ny;
+                let next = match self.get(nx, ny).and_then(&mut cost) {
+                    Some(step) => distance.saturating_add(step),
+                    None => continue,
+                };
+                if result.tiles[index].is_none_or(|current| next < current) {
+                    result.tiles[index] = Some(next);
+                    heap.push(Reverse((next, nx, ny)));
+                }
+            }
+        }
+        result
+    }
+}
+
+impl<T> HexGrid for HexArray<T> {
+    type Tile = T;
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn get(&self, x: usize, y: usize) -> Option<&T> {
+        HexArray::get(self, x, y)
+    }
+
+    fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        HexArray::get_mut(self, x, y)
+    }
+}
+
+impl<T, const H: usize, const W: usize> HexGrid for ConstHexArray<T, H, W> {
+    type Tile = T;
+
+    fn height(&self) -> usize {
+        H
+    }
+
+    fn width(&self) -> usize {
+        W
+    }
+
+    fn get(&self, x: usize, y: usize) -> Option<&T> {
+        ConstHexArray::get(self, x, y)
+    }
+
+    fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        ConstHexArray::get_mut(self, x, y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::collections::BTreeMap;
+
+    /// A grid storing only some of its tiles.
+    struct Sparse {
+        tiles: BTreeMap<(usize, usize), u32>,
+    }
+
+    impl HexGrid for Sparse {
+        type Tile = u32;
+
+        fn height(&self) -> usize {
+            4
+        }
+
+        fn width(&self) -> usize {
+            4
+        }
+
+        fn contains(&self, x: usize, y: usize) -> bool {
+            self.tiles.contains_key(&(x, y))
+        }
+
+        fn get(&self, x: usize, y: usize) -> Option<&u32> {
+            self.tiles.get(&(x, y))
+        }
+
+        fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut u32> {
+            self.tiles.get_mut(&(x, y))
+        }
+    }
+
+    // neighbors tests
+
+    #[test]
+    fn test_neighbors() {
+        let hex_array = HexArray::new(3, 4, 0);
+        for (x, y) in HexGrid::indices(&hex_array) {
+            let mut neighbors: Vec<_> = HexGrid::neighbors(&hex_array, x, y).collect();
+            let mut adjacent = hex_array.adjacent(x, y);
+            neighbors.sort();
+            adjacent.sort();
+            assert_eq!(neighbors, adjacent);
+        }
+    }
+
+    #[test]
+    fn test_neighbors_sparse() {
+        let tiles = [(0, 0), (0, 1), (1, 0), (2, 0)];
+        let sparse = Sparse {
+            tiles: tiles.into_iter().map(|index| (index, 1)).collect(),
+        };
+        assert_eq!(sparse.indices().collect::<Vec<_>>(), tiles);
+        assert_eq!(
+            sparse.neighbors(1, 0).collect::<Vec<_>>(),
+            vec![(2, 0), (0, 0), (0, 1)]
+        );
+    }
+
+    // distance_field tests
+
+    #[test]
+    fn test_distance_field_backends() {
+        let board = ConstHexArray::<u8, 4, 3>::new(1);
+        let hex_array = HexArray::from(board);
+        assert!(
+            HexGrid::distance_field(&board, [(0, 0)], |_| true)
+                == hex_array.distance_field([(0, 0)], |_| true)
+        );
+        assert!(
+            HexGrid::distance_field_weighted(&board, [(3, 2)], |tile| Some(*tile as u32))
+                == hex_array.distance_field_weighted([(3, 2)], |tile| Some(*tile as u32))
+        );
+    }
+
+    #[test]
+    fn test_distance_field_sparse() {
+        // (1, 0) is missing, so (2, 1) is only reached around it
+        let tiles = [(0, 0), (0, 1), (1, 1), (2, 1)];
+        let sparse = Sparse {
+            tiles: tiles.into_iter().map(|index| (index, 1)).collect(),
+        };
+        let distances = sparse.distance_field([(0, 0)], |_| true);
+        assert_eq!(distances.get(1, 1), Some(&Some(2)));
+        assert_eq!(distances.get(2, 1), Some(&Some(3)));
+        assert_eq!(distances.get(1, 0), Some(&None));
+        let weighted = sparse.distance_field_weighted([(0, 0), (3, 3)], |tile| Some(*tile * 2));
+        assert_eq!(weighted.get(2, 1), Some(&Some(6)));
+        assert_eq!(weighted.get(3, 3), Some(&None));
+    }
+}
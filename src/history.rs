@@ -0,0 +1,421 @@
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::Deref;
+
+use crate::HexArray;
+
+/// A reversible edit, holding the value to swap into the HexArray when it is applied.
+///
+/// Applying an edit turns it into the edit reverting it.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum Edit<T> {
+    Set {
+        x: usize,
+        y: usize,
+        value: T,
+    },
+    Replace {
+        height: usize,
+        width: usize,
+        tiles: Vec<T>,
+    },
+}
+
+impl<T> Edit<T> {
+    fn apply(&mut self, hex_array: &mut HexArray<T>) {
+        match self {
+            Edit::Set { x, y, value } => {
+                core::mem::swap(value, &mut hex_array.tiles[*x * hex_array.width + *y]);
+            }
+            Edit::Replace {
+                height,
+                width,
+                tiles,
+            } => {
+                core::mem::swap(height, &mut hex_array.height);
+                core::mem::swap(width, &mut hex_array.width);
+                core::mem::swap(tiles, &mut hex_array.tiles);
+            }
+        }
+    }
+
+    /// Get the number of tiles stored by the edit.
+    fn size(&self) -> usize {
+        match self {
+            Edit::Set { .. } => 1,
+            Edit::Replace { tiles, .. } => tiles.len(),
+        }
+    }
+}
+
+/// A HexArray recording its edits for undo and redo.
+///
+/// Every `set`, `fill`, `edit` and `resize` is one undo step, unless it is made inside
+/// `transaction`, which groups all edits made by a closure into a single step. The oldest steps
+/// are dropped once the steps together store more tiles than the limit.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct HexHistory<T> {
+    hex_array: HexArray<T>,
+    undo: VecDeque<Vec<Edit<T>>>,
+    redo: Vec<Vec<Edit<T>>>,
+    transaction: Option<Vec<Edit<T>>>,
+    size: usize,
+    limit: usize,
+}
+
+impl<T> HexHistory<T> {
+    /// Start recording edits to the given HexArray, without a limit.
+    pub fn new(hex_array: HexArray<T>) -> Self {
+        HexHistory::with_limit(hex_array, usize::MAX)
+    }
+
+    /// Start recording edits to the given HexArray, keeping undo steps storing at most `limit`
+    /// tiles in total.
+    pub fn with_limit(hex_array: HexArray<T>, limit: usize) -> Self {
+        HexHistory {
+            hex_array,
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            transaction: None,
+            size: 0,
+            limit,
+        }
+    }
+
+    /// Stop recording edits and get the HexArray.
+    pub fn into_inner(self) -> HexArray<T> {
+        self.hex_array
+    }
+
+    fn push_undo(&mut self, step: Vec<Edit<T>>) {
+        self.size += step.iter().map(Edit::size).sum::<usize>();
+        self.undo.push_back(step);
+        while self.size > self.limit {
+            match self.undo.pop_front() {
+                Some(step) => self.size -= step.iter().map(Edit::size).sum::<usize>(),
+                None => break,
+            }
+        }
+    }
+
+    /// Close the transaction being recorded, if it has any edits.
+    fn close_transaction(&mut self) {
+        if let Some(step) = self.transaction.take() {
+            if !step.is_empty() {
+                self.push_undo(step);
+            }
+        }
+    }
+
+    fn record(&mut self, edit: Edit<T>) {
+        self.redo.clear();
+        match &mut self.transaction {
+            Some(step) => step.push(edit),
+            None => self.push_undo(vec![edit]),
+        }
+    }
+
+    /// Set the value at the given indices.
+    pub fn set(&mut self, x: usize, y: usize, value: T) -> Option<()> {
+        let tile = self.hex_array.get_mut(x, y)?;
+        let value = core::mem::replace(tile, value);
+        self.record(Edit::Set { x, y, value });
+        Some(())
+    }
+
+    /// Run the closure, recording all of its edits as a single undo step.
+    ///
+    /// Transactions inside a transaction become part of the outer one.
+    pub fn transaction<R, F>(&mut self, f: F) -> R
+    where
+        F: FnOnce(&mut Self) -> R,
+    {
+        if self.transaction.is_some() {
+            return f(self);
+        }
+        self.transaction = Some(Vec::new());
+        let result = f(self);
+        self.close_transaction();
+        result
+    }
+
+    /// Check whether there is an edit to undo.
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+            || self
+                .transaction
+                .as_ref()
+                .is_some_and(|step| !step.is_empty())
+    }
+
+    /// Check whether there is an undone edit to redo.
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Revert the last undo step, returning false if there is none.
+    ///
+    /// Undoing inside a transaction reverts the edits made in it so far.
+    pub fn undo(&mut self) -> bool {
+        if self.transaction.is_some() {
+            self.close_transaction();
+            self.transaction = Some(Vec::new());
+        }
+        let Some(mut step) = self.undo.pop_back() else {
+            return false;
+        };
+        self.size -= step.iter().map(Edit::size).sum::<usize>();
+        for edit in step.iter_mut().rev() {
+            edit.apply(&mut self.hex_array);
+        }
+        self.redo.push(step);
+        true
+    }
+
+    /// Reapply the last undone step, returning false if there is none.
+    pub fn redo(&mut self) -> bool {
+        let Some(mut step) = self.redo.pop() else {
+            return false;
+        };
+        for edit in &mut step {
+            edit.apply(&mut self.hex_array);
+        }
+        self.push_undo(step);
+        true
+    }
+
+    /// Forget every recorded edit.
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        if let Some(step) = &mut self.transaction {
+            step.clear();
+        }
+        self.size = 0;
+    }
+}
+
+impl<T> HexHistory<T>
+where
+    T: Clone,
+{
+    /// Resize the HexArray, keeping the tiles at indices inside both sizes and setting new tiles
+    /// to the given value.
+    pub fn resize(&mut self, height: usize, width: usize, value: T) {
+        let edit = Edit::Replace {
+            height: self.hex_array.height,
+            width: self.hex_array.width,
+            tiles: self.hex_array.tiles.clone(),
+        };
+        self.hex_array.resize(height, width, value);
+        self.record(edit);
+    }
+}
+
+impl<T> HexHistory<T>
+where
+    T: Clone + PartialEq,
+{
+    /// Set every tile at the given indices to the given value, ignoring indices outside the
+    /// HexArray.
+    ///
+    /// Returns the number of tiles whose value changed; unchanged tiles are not recorded.
+    pub fn fill<I>(&mut self, tiles: I, value: T) -> usize
+    where
+        I: IntoIterator<Item = (usize, usize)>,
+    {
+        self.transaction(|history| {
+            let mut changed = 0;
+            for (x, y) in tiles {
+                if history.get(x, y).is_some_and(|tile| *tile != value) {
+                    history.set(x, y, value.clone());
+                    changed += 1;
+                }
+            }
+            changed
+        })
+    }
+
+    /// Run the closure on the HexArray, recording the tiles it changes as a single undo step.
+    ///
+    /// This records any edit, such as `HexArray::fill_range` or `HexArray::fill_region`, at the
+    /// cost of copying the HexArray to compare it afterwards.
+    pub fn edit<R, F>(&mut self, f: F) -> R
+    where
+        F: FnOnce(&mut HexArray<T>) -> R,
+    {
+        let before = self.hex_array.clone();
+        let result = f(&mut self.hex_array);
+        if (before.height, before.width) != (self.hex_array.height, self.hex_array.width) {
+            self.record(Edit::Replace {
+                height: before.height,
+                width: before.width,
+                tiles: before.tiles,
+            });
+            return result;
+        }
+        self.transaction(|history| {
+            let width = before.width;
+            for (index, value) in before.tiles.into_iter().enumerate() {
+                if history.hex_array.tiles[index] != value {
+                    history.record(Edit::Set {
+                        x: index / width,
+                        y: index % width,
+                        value,
+                    });
+                }
+            }
+        });
+        result
+    }
+}
+
+impl<T> Deref for HexHistory<T> {
+    type Target = HexArray<T>;
+
+    fn deref(&self) -> &HexArray<T> {
+        &self.hex_array
+    }
+}
+
+impl<T> From<HexArray<T>> for HexHistory<T> {
+    fn from(hex_array: HexArray<T>) -> Self {
+        HexHistory::new(hex_array)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // undo tests
+
+    #[test]
+    fn test_undo_redo() {
+        let mut history = HexHistory::new(HexArray::new(2, 2, 0));
+        assert!(!history.can_undo());
+        assert_eq!(history.set(0, 0, 1), Some(()));
+        assert_eq!(history.set(0, 0, 2), Some(()));
+        assert_eq!(history.set(2, 0, 3), None);
+        assert!(history.undo());
+        assert_eq!(history.get(0, 0), Some(&1));
+        assert!(history.undo());
+        assert_eq!(history.get(0, 0), Some(&0));
+        assert!(!history.undo());
+        assert!(history.redo());
+        assert_eq!(history.get(0, 0), Some(&1));
+        assert!(history.can_redo());
+        history.set(1, 1, 4);
+        assert!(!history.can_redo());
+        assert!(!history.redo());
+        let hex_array = history.into_inner();
+        assert_eq!(hex_array.get(0, 0), Some(&1));
+        assert_eq!(hex_array.get(1, 1), Some(&4));
+    }
+
+    #[test]
+    fn test_transaction() {
+        let mut history = HexHistory::new(HexArray::new(3, 3, 0));
+        history.set(2, 2, 9);
+        let count = history.transaction(|history| {
+            history.set(0, 0, 1);
+            history.transaction(|history| history.set(0, 0, 2));
+            assert_eq!(history.fill([(1, 1), (1, 2), (1, 2), (5, 5)], 3), 2);
+            3
+        });
+        assert_eq!(count, 3);
+        assert_eq!(history.get(1, 2), Some(&3));
+        assert!(history.undo());
+        assert_eq!(history.get(0, 0), Some(&0));
+        assert_eq!(history.get(1, 1), Some(&0));
+        assert_eq!(history.get(2, 2), Some(&9));
+        assert!(history.redo());
+        assert_eq!(history.get(0, 0), Some(&2));
+        assert_eq!(history.get(1, 2), Some(&3));
+    }
+
+    #[test]
+    fn test_undo_in_transaction() {
+        let mut history = HexHistory::new(HexArray::new(1, 2, 0));
+        history.set(0, 1, 5);
+        history.transaction(|history| {
+            history.set(0, 0, 1);
+            assert!(history.undo());
+            assert_eq!(history.get(0, 0), Some(&0));
+            history.set(0, 0, 2);
+        });
+        assert!(history.undo());
+        assert_eq!(history.get(0, 0), Some(&0));
+        assert_eq!(history.get(0, 1), Some(&5));
+    }
+
+    #[test]
+    fn test_fill_unchanged() {
+        let mut history = HexHistory::with_limit(HexArray::new(2, 2, 0), 2);
+        history.set(0, 0, 1);
+        assert_eq!(history.fill([(0, 0), (1, 1)], 0), 1);
+        assert_eq!(history.fill([(0, 1), (1, 1)], 0), 0);
+        assert!(history.undo());
+        assert!(history.undo());
+        assert_eq!(history.get(0, 0), Some(&0));
+        assert!(!history.undo());
+    }
+
+    // edit tests
+
+    #[test]
+    fn test_edit() {
+        let mut history = HexHistory::new(HexArray::new(5, 5, 0));
+        let changed = history.edit(|hex_array| hex_array.fill_range((2, 2), 1, 1));
+        assert_eq!(changed, 7);
+        history.edit(|hex_array| hex_array.fill_ring((2, 2), 2, 2));
+        history.edit(|hex_array| hex_array.resize(2, 2, 0));
+        assert_eq!((history.height(), history.width()), (2, 2));
+        assert!(history.undo());
+        assert_eq!(history.get(2, 4), Some(&2));
+        assert_eq!(history.get(2, 3), Some(&1));
+        assert!(history.undo());
+        assert!(history.undo());
+        assert!(history.fold(true, |zero, tile| zero && *tile == 0));
+        assert!(!history.undo());
+        assert!(format!("{:?}", history).starts_with("HexHistory {"));
+    }
+
+    // resize tests
+
+    #[test]
+    fn test_resize() {
+        let mut history = HexHistory::new(HexArray::new(2, 2, 1));
+        history.set(1, 1, 2);
+        history.resize(1, 3, 0);
+        assert_eq!((history.height(), history.width()), (1, 3));
+        history.set(0, 2, 3);
+        assert!(history.undo());
+        assert!(history.undo());
+        assert_eq!((history.height(), history.width()), (2, 2));
+        assert_eq!(history.get(1, 1), Some(&2));
+        assert!(history.redo());
+        assert!(history.redo());
+        assert_eq!(history.get(0, 2), Some(&3));
+    }
+
+    // limit tests
+
+    #[test]
+    fn test_limit() {
+        let mut history = HexHistory::with_limit(HexArray::new(4, 4, 0), 3);
+        for y in 0..4 {
+            history.set(0, y, 1);
+        }
+        assert!(history.undo());
+        assert!(history.undo());
+        assert!(history.undo());
+        assert!(!history.undo());
+        assert_eq!(history.get(0, 0), Some(&1));
+        history.resize(5, 5, 0);
+        assert!(!history.can_undo());
+        history.clear();
+        assert!(!history.can_redo());
+    }
+}
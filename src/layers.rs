@@ -0,0 +1,556 @@
+use core::ops::Deref;
+
+use crate::{HexArray, HexGrid};
+
+/// Tuples of HexArrays that can be stored together in a HexLayers.
+///
+/// Implemented for tuples of up to eight HexArrays with `Clone` tiles. Tile indices passed to
+/// these methods are indices into the flat storage shared by every layer.
+pub trait Layers {
+    /// One value for every layer.
+    type Values;
+
+    /// References to the values of one tile in every layer.
+    type Refs<'a>
+    where
+        Self: 'a;
+
+    /// Mutable references to the values of one tile in every layer.
+    type Muts<'a>
+    where
+        Self: 'a;
+
+    /// Mutable views of every layer.
+    type LayersMut<'a>
+    where
+        Self: 'a;
+
+    /// Create layers with the given dimensions and every tile set to the given values.
+    fn filled(height: usize, width: usize, values: Self::Values) -> Self;
+
+    /// Get the dimensions of the layers if they are all the same.
+    fn dimensions(&self) -> Option<(usize, usize)>;
+
+    /// Resize every layer, setting new tiles to the given values.
+    fn resize(&mut self, height: usize, width: usize, values: Self::Values);
+
+    /// Get references to the values of the tile at the given index in every layer.
+    fn tile(&self, index: usize) -> Self::Refs<'_>;
+
+    /// Get mutable references to the values of the tile at the given index in every layer.
+    fn tile_mut(&mut self, index: usize) -> Self::Muts<'_>;
+
+    /// Set the values of the tile at the given index in every layer.
+    fn set_tile(&mut self, index: usize, values: Self::Values);
+
+    /// Get mutable views of every layer.
+    fn layers_mut(&mut self) -> Self::LayersMut<'_>;
+
+    /// Iterate over the values of every tile in every layer, in index order.
+    fn tiles(&self) -> impl Iterator<Item = Self::Refs<'_>>;
+
+    /// Iterate over mutable references to the values of every tile in every layer, in index order.
+    fn tiles_mut(&mut self) -> impl Iterator<Item = Self::Muts<'_>>;
+}
+
+macro_rules! impl_layers {
+    ($(($T:ident, $t:ident, $i:tt)),+) => {
+        impl<$($T),+> Layers for ($(HexArray<$T>,)+)
+        where
+            $($T: Clone,)+
+        {
+            type Values = ($($T,)+);
+            type Refs<'a> = ($(&'a $T,)+) where Self: 'a;
+            type Muts<'a> = ($(&'a mut $T,)+) where Self: 'a;
+            type LayersMut<'a> = ($(LayerMut<'a, $T>,)+) where Self: 'a;
+
+            fn filled(height: usize, width: usize, values: Self::Values) -> Self {
+                ($(HexArray::new(height, width, values.$i),)+)
+            }
+
+            fn dimensions(&self) -> Option<(usize, usize)> {
+                let dimensions = (self.0.height, self.0.width);
+                if $((self.$i.height, self.$i.width) == dimensions)&&+ {
+                    Some(dimensions)
+                } else {
+                    None
+                }
+            }
+
+            fn resize(&mut self, height: usize, width: usize, values: Self::Values) {
+                $(self.$i.resize(height, width, values.$i);)+
+            }
+
+            fn tile(&self, index: usize) -> Self::Refs<'_> {
+                ($(&self.$i.tiles[index],)+)
+            }
+
+            fn tile_mut(&mut self, index: usize) -> Self::Muts<'_> {
+                ($(&mut self.$i.tiles[index],)+)
+            }
+
+            fn set_tile(&mut self, index: usize, values: Self::Values) {
+                $(self.$i.tiles[index] = values.$i;)+
+            }
+
+            fn layers_mut(&mut self) -> Self::LayersMut<'_> {
+                ($(LayerMut { hex_array: &mut self.$i },)+)
+            }
+
+            fn tiles(&self) -> impl Iterator<Item = Self::Refs<'_>> {
+                let ($(mut $t,)+) = ($(self.$i.tiles.iter(),)+);
+                core::iter::from_fn(move || Some(($($t.next()?,)+)))
+            }
+
+            fn tiles_mut(&mut self) -> impl Iterator<Item = Self::Muts<'_>> {
+                let ($(mut $t,)+) = ($(self.$i.tiles.iter_mut(),)+);
+                core::iter::from_fn(move || Some(($($t.next()?,)+)))
+            }
+        }
+    };
+}
+
+impl_layers!((A, a, 0));
+impl_layers!((A, a, 0), (B, b, 1));
+impl_layers!((A, a, 0), (B, b, 1), (C, c, 2));
+impl_layers!((A, a, 0), (B, b, 1), (C, c, 2), (D, d, 3));
+impl_layers!((A, a, 0), (B, b, 1), (C, c, 2), (D, d, 3), (E, e, 4));
+impl_layers!(
+    (A, a, 0),
+    (B, b, 1),
+    (C, c, 2),
+    (D, d, 3),
+    (E, e, 4),
+    (F, f, 5)
+);
+impl_layers!(
+    (A, a, 0),
+    (B, b, 1),
+    (C, c, 2),
+    (D, d, 3),
+    (E, e, 4),
+    (F, f, 5),
+    (G, g, 6)
+);
+impl_layers!(
+    (A, a, 0),
+    (B, b, 1),
+    (C, c, 2),
+    (D, d, 3),
+    (E, e, 4),
+    (F, f, 5),
+    (G, g, 6),
+    (H, h, 7)
+);
+
+/// Layers chosen from a HexLayers to iterate over together.
+///
+/// Implemented for a `&HexArray` or `LayerMut`, and for tuples of up to eight of either.
+pub trait LayerSelection<'a> {
+    /// The values of one tile in the chosen layers.
+    type Item;
+
+    /// Iterate over the values of every tile in the chosen layers, in index order, stopping at
+    /// the end of the shortest layer.
+    fn zip(self) -> impl Iterator<Item = Self::Item>;
+}
+
+impl<'a, T> LayerSelection<'a> for &'a HexArray<T> {
+    type Item = &'a T;
+
+    fn zip(self) -> impl Iterator<Item = &'a T> {
+        self.tiles.iter()
+    }
+}
+
+impl<'a, T> LayerSelection<'a> for LayerMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn zip(self) -> impl Iterator<Item = &'a mut T> {
+        self.hex_array.tiles.iter_mut()
+    }
+}
+
+macro_rules! impl_layer_selection {
+    ($(($T:ident, $t:ident, $i:tt)),+) => {
+        impl<'a, $($T),+> LayerSelection<'a> for ($(&'a HexArray<$T>,)+) {
+            type Item = ($(&'a $T,)+);
+
+            fn zip(self) -> impl Iterator<Item = Self::Item> {
+                let ($(mut $t,)+) = ($(self.$i.tiles.iter(),)+);
+                core::iter::from_fn(move || Some(($($t.next()?,)+)))
+            }
+        }
+
+        impl<'a, $($T),+> LayerSelection<'a> for ($(LayerMut<'a, $T>,)+) {
+            type Item = ($(&'a mut $T,)+);
+
+            fn zip(self) -> impl Iterator<Item = Self::Item> {
+                let ($(mut $t,)+) = ($(self.$i.hex_array.tiles.iter_mut(),)+);
+                core::iter::from_fn(move || Some(($($t.next()?,)+)))
+            }
+        }
+    };
+}
+
+impl_layer_selection!((A, a, 0));
+impl_layer_selection!((A, a, 0), (B, b, 1));
+impl_layer_selection!((A, a, 0), (B, b, 1), (C, c, 2));
+impl_layer_selection!((A, a, 0), (B, b, 1), (C, c, 2), (D, d, 3));
+impl_layer_selection!((A, a, 0), (B, b, 1), (C, c, 2), (D, d, 3), (E, e, 4));
+impl_layer_selection!(
+    (A, a, 0),
+    (B, b, 1),
+    (C, c, 2),
+    (D, d, 3),
+    (E, e, 4),
+    (F, f, 5)
+);
+impl_layer_selection!(
+    (A, a, 0),
+    (B, b, 1),
+    (C, c, 2),
+    (D, d, 3),
+    (E, e, 4),
+    (F, f, 5),
+    (G, g, 6)
+);
+impl_layer_selection!(
+    (A, a, 0),
+    (B, b, 1),
+    (C, c, 2),
+    (D, d, 3),
+    (E, e, 4),
+    (F, f, 5),
+    (G, g, 6),
+    (H, h, 7)
+);
+
+/// HexArrays of identical dimensions stored side by side, one for every field of a tile.
+///
+/// `L` is a tuple of HexArrays such as `(HexArray<Terrain>, HexArray<u8>)`, so systems touching a
+/// single field read one contiguous layer. The layers can be read directly with `layers`, while
+/// mutable access goes through `LayerMut` views that cannot change the dimensions of a layer.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct HexLayers<L> {
+    height: usize,
+    width: usize,
+    layers: L,
+}
+
+impl<L> HexLayers<L>
+where
+    L: Layers,
+{
+    /// Create a new HexLayers with the given height, width, and default value for every layer.
+    pub fn new(height: usize, width: usize, defaults: L::Values) -> Self {
+        HexLayers {
+            height,
+            width,
+            layers: L::filled(height, width, defaults),
+        }
+    }
+
+    /// Create a new HexLayers from existing layers, if their dimensions all match.
+    pub fn from_layers(layers: L) -> Option<Self> {
+        let (height, width) = layers.dimensions()?;
+        Some(HexLayers {
+            height,
+            width,
+            layers,
+        })
+    }
+
+    /// Get the height of every layer.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Get the width of every layer.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Get the layers.
+    pub fn layers(&self) -> &L {
+        &self.layers
+    }
+
+    /// Get mutable views of the layers.
+    pub fn layers_mut(&mut self) -> L::LayersMut<'_> {
+        self.layers.layers_mut()
+    }
+
+    /// Take the layers out of the HexLayers.
+    pub fn into_layers(self) -> L {
+        self.layers
+    }
+
+    fn index(&self, x: usize, y: usize) -> Option<usize> {
+        if x < self.height && y < self.width {
+            Some(x * self.width + y)
+        } else {
+            None
+        }
+    }
+
+    /// Get the values at the given indices in every layer.
+    pub fn get(&self, x: usize, y: usize) -> Option<L::Refs<'_>> {
+        Some(self.layers.tile(self.index(x, y)?))
+    }
+
+    /// Get mutable references to the values at the given indices in every layer.
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<L::Muts<'_>> {
+        let index = self.index(x, y)?;
+        Some(self.layers.tile_mut(index))
+    }
+
+    /// Set the values at the given indices in every layer.
+    pub fn set(&mut self, x: usize, y: usize, values: L::Values) -> Option<()> {
+        let index = self.index(x, y)?;
+        self.layers.set_tile(index, values);
+        Some(())
+    }
+
+    /// Resize every layer, keeping the tiles at indices inside both sizes and setting new tiles to
+    /// the given values.
+    pub fn resize(&mut self, height: usize, width: usize, defaults: L::Values) {
+        self.layers.resize(height, width, defaults);
+        self.height = height;
+        self.width = width;
+    }
+
+    /// Iterate over the indices and the values in every layer of every tile.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), L::Refs<'_>)> {
+        let width = self.width;
+        self.layers
+            .tiles()
+            .enumerate()
+            .map(move |(index, tile)| ((index / width, index % width), tile))
+    }
+
+    /// Iterate over the indices and mutable references to the values in every layer of every tile.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = ((usize, usize), L::Muts<'_>)> {
+        let width = self.width;
+        self.layers
+            .tiles_mut()
+            .enumerate()
+            .map(move |(index, tile)| ((index / width, index % width), tile))
+    }
+
+    /// Iterate over the indices and the values in the chosen layers of every tile, reading only
+    /// those layers.
+    ///
+    /// The closure picks the layers from `layers`, such as `|(terrain, _, fog)| (terrain, fog)`.
+    pub fn iter_layers<'a, S, F>(
+        &'a self,
+        select: F,
+    ) -> impl Iterator<Item = ((usize, usize), S::Item)>
+    where
+        S: LayerSelection<'a>,
+        F: FnOnce(&'a L) -> S,
+    {
+        let width = self.width;
+        select(&self.layers)
+            .zip()
+            .enumerate()
+            .map(move |(index, tile)| ((index / width, index % width), tile))
+    }
+
+    /// Iterate over the indices and mutable references to the values in the chosen layers of
+    /// every tile, touching only those layers.
+    ///
+    /// The closure picks the views from `layers_mut`, such as `|(terrain, _, fog)| (terrain, fog)`.
+    pub fn iter_layers_mut<'a, S, F>(
+        &'a mut self,
+        select: F,
+    ) -> impl Iterator<Item = ((usize, usize), S::Item)>
+    where
+        S: LayerSelection<'a>,
+        F: FnOnce(L::LayersMut<'a>) -> S,
+    {
+        let width = self.width;
+        select(self.layers.layers_mut())
+            .zip()
+            .enumerate()
+            .map(move |(index, tile)| ((index / width, index % width), tile))
+    }
+}
+
+/// A mutable view of one layer of a HexLayers.
+///
+/// Dereferences to the HexArray of the layer and allows changing its tiles but not its dimensions.
+#[derive(Debug)]
+pub struct LayerMut<'a, T> {
+    hex_array: &'a mut HexArray<T>,
+}
+
+impl<T> LayerMut<'_, T> {
+    /// Get a mutable reference to the value at the given indices.
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        self.hex_array.get_mut(x, y)
+    }
+
+    /// Set the value at the given indices.
+    pub fn set(&mut self, x: usize, y: usize, value: T) -> Option<()> {
+        self.hex_array.set(x, y, value)
+    }
+
+    /// Iterate over the indices and mutable references to the values of every tile.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = ((usize, usize), &mut T)> {
+        let width = self.hex_array.width;
+        self.hex_array
+            .tiles
+            .iter_mut()
+            .enumerate()
+            .map(move |(index, tile)| ((index / width, index % width), tile))
+    }
+}
+
+impl<T> Deref for LayerMut<'_, T> {
+    type Target = HexArray<T>;
+
+    fn deref(&self) -> &HexArray<T> {
+        self.hex_array
+    }
+}
+
+impl<T> HexGrid for LayerMut<'_, T> {
+    type Tile = T;
+
+    fn height(&self) -> usize {
+        self.hex_array.height
+    }
+
+    fn width(&self) -> usize {
+        self.hex_array.width
+    }
+
+    fn get(&self, x: usize, y: usize) -> Option<&T> {
+        self.hex_array.get(x, y)
+    }
+
+    fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        self.hex_array.get_mut(x, y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Map = HexLayers<(HexArray<char>, HexArray<u8>, HexArray<bool>)>;
+
+    // new tests
+
+    #[test]
+    fn test_new() {
+        let map = Map::new(3, 4, ('.', 0, false));
+        assert_eq!(map.height(), 3);
+        assert_eq!(map.width(), 4);
+        assert_eq!(map.get(2, 3), Some((&'.', &0, &false)));
+        assert_eq!(map.get(3, 0), None);
+        assert_eq!(map.layers().1.height(), 3);
+    }
+
+    #[test]
+    fn test_from_layers() {
+        let layers = (HexArray::new(2, 2, 'a'), HexArray::new(2, 2, 1u8));
+        let map = HexLayers::from_layers(layers).unwrap();
+        assert_eq!(map.get(1, 1), Some((&'a', &1)));
+        let (terrain, elevation) = map.into_layers();
+        assert_eq!(terrain.width(), 2);
+        assert_eq!(elevation.width(), 2);
+        let layers = (HexArray::new(2, 2, 'a'), HexArray::new(2, 3, 1u8));
+        assert!(HexLayers::from_layers(layers).is_none());
+    }
+
+    // get tests
+
+    #[test]
+    fn test_get_set() {
+        let mut map = Map::new(2, 2, ('.', 0, false));
+        assert_eq!(map.set(1, 0, ('#', 3, true)), Some(()));
+        assert_eq!(map.set(2, 0, ('#', 3, true)), None);
+        let (terrain, elevation, _) = map.get_mut(0, 1).unwrap();
+        *terrain = '~';
+        *elevation = 7;
+        assert_eq!(map.get(1, 0), Some((&'#', &3, &true)));
+        assert_eq!(map.get(0, 1), Some((&'~', &7, &false)));
+    }
+
+    #[test]
+    fn test_layers_mut() {
+        let mut map = Map::new(3, 3, ('.', 1, false));
+        let (_, mut elevation, mut visible) = map.layers_mut();
+        elevation.set(0, 0, 2);
+        let distances = elevation.distance_field([(0, 0)], |_| true);
+        for ((x, y), tile) in visible.iter_mut() {
+            *tile = distances.get(x, y) == Some(&Some(1));
+        }
+        *HexGrid::get_mut(&mut elevation, 2, 2).unwrap() = 5;
+        assert_eq!(map.get(0, 0), Some((&'.', &2, &false)));
+        assert_eq!(map.get(1, 0), Some((&'.', &1, &true)));
+        assert_eq!(map.get(2, 2), Some((&'.', &5, &false)));
+        assert_eq!(
+            map.layers().2.fold(0, |count, tile| count + *tile as u32),
+            2
+        );
+    }
+
+    // resize tests
+
+    #[test]
+    fn test_resize() {
+        let mut map = Map::new(2, 2, ('.', 0, false));
+        map.set(1, 1, ('#', 1, true));
+        map.resize(3, 1, ('~', 2, false));
+        assert_eq!(map.height(), 3);
+        assert_eq!(map.width(), 1);
+        assert_eq!(map.get(1, 0), Some((&'.', &0, &false)));
+        assert_eq!(map.get(2, 0), Some((&'~', &2, &false)));
+        assert_eq!(map.layers().0.width(), 1);
+        assert_eq!(map.layers().2.height(), 3);
+    }
+
+    // iter tests
+
+    #[test]
+    fn test_iter() {
+        let mut map = Map::new(2, 3, ('.', 0, false));
+        for ((x, y), (_, elevation, visible)) in map.iter_mut() {
+            *elevation = (x * 3 + y) as u8;
+            *visible = y == 2;
+        }
+        let tiles: Vec<_> = map
+            .iter()
+            .filter(|(_, (_, _, visible))| **visible)
+            .map(|(index, (_, elevation, _))| (index, *elevation))
+            .collect();
+        assert_eq!(tiles, [((0, 2), 2), ((1, 2), 5)]);
+    }
+
+    #[test]
+    fn test_iter_layers() {
+        let mut map = Map::new(2, 2, ('.', 0, false));
+        for ((x, _), (terrain, visible)) in
+            map.iter_layers_mut(|(terrain, _, visible)| (terrain, visible))
+        {
+            *terrain = if x == 0 { '~' } else { '^' };
+            *visible = x == 1;
+        }
+        for (_, elevation) in map.iter_layers_mut(|(_, elevation, _)| elevation) {
+            *elevation += 2;
+        }
+        let tiles: Vec<_> = map
+            .iter_layers(|(terrain, _, visible)| (terrain, visible))
+            .filter(|(_, (_, visible))| **visible)
+            .map(|(index, (terrain, _))| (index, *terrain))
+            .collect();
+        assert_eq!(tiles, [((1, 0), '^'), ((1, 1), '^')]);
+        assert!(map
+            .iter_layers(|layers| &layers.1)
+            .all(|(_, elevation)| *elevation == 2));
+        assert!(format!("{:?}", map).starts_with("HexLayers { height: 2, width: 2"));
+    }
+}
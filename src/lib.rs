@@ -1,6 +1,66 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+#[cfg(not(any(feature = "std", feature = "libm")))]
+compile_error!("either the `std` or the `libm` feature must be enabled");
+
+#[cfg(any(feature = "serde", feature = "tiled"))]
+mod base64;
+#[cfg(feature = "bevy")]
+pub mod bevy;
+mod bit_array;
+mod const_array;
+mod csv;
+mod direction;
+mod distance;
+mod edge;
+mod fill;
+#[cfg(feature = "petgraph")]
+pub mod graph;
+mod grid;
+mod history;
+mod layers;
+pub mod mesh;
+mod patch;
+mod point;
+#[cfg(feature = "procgen")]
+pub mod procgen;
+#[cfg(feature = "rand")]
+mod random;
+#[cfg(feature = "image")]
+mod raster;
 #[cfg(feature = "serde")]
-mod serde;
-
+pub mod serde;
+pub mod svg;
+mod text;
+#[cfg(feature = "tiled")]
+pub mod tiled;
+mod tracked;
+mod transform;
+mod vertex;
+#[cfg(feature = "wfc")]
+pub mod wfc;
+
+pub use bit_array::HexBitArray;
+pub use const_array::ConstHexArray;
+pub use csv::CsvError;
+pub use direction::Direction;
+pub use edge::{EdgeArray, HexEdge};
+pub use grid::HexGrid;
+pub use history::HexHistory;
+pub use layers::{HexLayers, LayerMut, LayerSelection, Layers};
+pub use patch::HexPatch;
+pub use point::Point;
+pub use text::{ParseTextError, TextStyle};
+pub use tracked::{Changes, TrackedHexArray};
+pub use transform::Border;
+pub use vertex::{HexVertex, VertexArray};
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct HexArray<T> {
     height: usize,
     width: usize,
@@ -19,6 +79,23 @@ where
             tiles: vec![default; height * width],
         }
     }
+
+    /// Resize the HexArray, keeping the tiles at indices inside both sizes and setting new tiles
+    /// to the given value.
+    pub fn resize(&mut self, height: usize, width: usize, value: T) {
+        let mut tiles = Vec::with_capacity(height * width);
+        for x in 0..height {
+            for y in 0..width {
+                tiles.push(match self.get(x, y) {
+                    Some(tile) => tile.clone(),
+                    None => value.clone(),
+                });
+            }
+        }
+        self.height = height;
+        self.width = width;
+        self.tiles = tiles;
+    }
 }
 
 impl<T> HexArray<T> {
@@ -60,53 +137,56 @@ impl<T> HexArray<T> {
         }
     }
 
-    fn pos(x: usize, y: usize) -> (f32, f32) {
-        const SQRT_3: f32 = 1.732050807568877293527446341505872367_f32;
-        const HALF_SQRT_3: f32 = 0.8660254037844386467637231707529361835_f32;
-        if x % 2 == 0 {
-            let x = x as f32 * 1.5;
-            let y = y as f32 * SQRT_3;
-            (x, y)
+    /// Get the indices as unsigned if they are inside the HexArray.
+    pub(crate) fn in_bounds(&self, x: isize, y: isize) -> Option<(usize, usize)> {
+        if (0..self.height as isize).contains(&x) && (0..self.width as isize).contains(&y) {
+            Some((x as usize, y as usize))
         } else {
-            let x = x as f32 * 1.5;
-            let y = y as f32 * SQRT_3 + HALF_SQRT_3;
-            (x, y)
+            None
         }
     }
 
-    #[cfg(feature = "glam")]
     /// Get the position of the tile at the given indices.
-    pub fn position(x: usize, y: usize) -> glam::Vec2 {
-        let (x, y) = Self::pos(x, y);
-        glam::Vec2::new(x, y)
+    pub fn position(x: usize, y: usize) -> Point {
+        pos(x as isize, y as isize).into()
     }
 
-    #[cfg(not(feature = "glam"))]
-    /// Get the position of the tile at the given indices.
-    pub fn position(x: usize, y: usize) -> (f32, f32) {
-        Self::pos(x, y)
+    /// Get the position of the tile at the given indices, with double precision.
+    pub fn position_f64(x: usize, y: usize) -> Point<f64> {
+        pos_f64(x as isize, y as isize).into()
+    }
+
+    /// Get the indices of the tile containing the given position.
+    pub fn tile_at(&self, x: f32, y: f32) -> Option<(usize, usize)> {
+        self.tile_at_f64(x as f64, y as f64)
+    }
+
+    /// Get the indices of the tile containing the given position, with double precision.
+    pub fn tile_at_f64(&self, x: f64, y: f64) -> Option<(usize, usize)> {
+        let (x, y) = pos_to_tile_within(self.height, self.width, x, y)?;
+        self.in_bounds(x, y)
     }
 
     /// Get the indices of the tiles adjacent to the given indices.
     pub fn adjacent(&self, x: usize, y: usize) -> Vec<(usize, usize)> {
         let mut result = Vec::new();
-        if x % 2 == 0 {
+        if x.is_multiple_of(2) {
             if y > 0 {
                 result.push((x, y - 1));
-                if x < self.width - 1 {
+                if x + 1 < self.height {
                     result.push((x + 1, y - 1));
                 }
                 if x > 0 {
                     result.push((x - 1, y - 1));
                 }
             }
-            if y < self.height - 1 {
+            if y + 1 < self.width {
                 result.push((x, y + 1));
             }
         } else {
-            if y < self.height - 1 {
+            if y + 1 < self.width {
                 result.push((x, y + 1));
-                if x < self.width - 1 {
+                if x + 1 < self.height {
                     result.push((x + 1, y + 1));
                 }
                 if x > 0 {
@@ -117,7 +197,7 @@ impl<T> HexArray<T> {
                 result.push((x, y - 1));
             }
         }
-        if x < self.width - 1 {
+        if x + 1 < self.height {
             result.push((x + 1, y));
         }
         if x > 0 {
@@ -127,6 +207,142 @@ impl<T> HexArray<T> {
     }
 }
 
+/// Get the position of the tile at the given, possibly out of bounds, indices.
+pub(crate) fn pos(x: isize, y: isize) -> (f32, f32) {
+    const SQRT_3: f32 = 1.732050807568877293527446341505872367_f32;
+    const HALF_SQRT_3: f32 = 0.8660254037844386467637231707529361835_f32;
+    if x.rem_euclid(2) == 0 {
+        let x = x as f32 * 1.5;
+        let y = y as f32 * SQRT_3;
+        (x, y)
+    } else {
+        let x = x as f32 * 1.5;
+        let y = y as f32 * SQRT_3 + HALF_SQRT_3;
+        (x, y)
+    }
+}
+
+/// Get the position of the tile at the given, possibly out of bounds, indices, with double
+/// precision.
+pub(crate) fn pos_f64(x: isize, y: isize) -> (f64, f64) {
+    const SQRT_3: f64 = 1.732050807568877293527446341505872367_f64;
+    const HALF_SQRT_3: f64 = 0.8660254037844386467637231707529361835_f64;
+    let offset = if x.rem_euclid(2) == 0 {
+        0.0
+    } else {
+        HALF_SQRT_3
+    };
+    (x as f64 * 1.5, y as f64 * SQRT_3 + offset)
+}
+
+/// Get the, possibly out of bounds, indices of the tile containing the given position.
+pub(crate) fn pos_to_tile(x: f64, y: f64) -> (isize, isize) {
+    const SQRT_3: f64 = 1.732050807568877293527446341505872367_f64;
+    let q = x / 1.5;
+    let r = y / SQRT_3 - q / 2.0;
+    let s = -q - r;
+    let (mut rq, mut rr, rs) = (round(q), round(r), round(s));
+    let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+    if dq > dr && dq > ds {
+        rq = -rr - rs;
+    } else if dr > ds {
+        rr = -rq - rs;
+    }
+    from_axial(rq as isize, rr as isize)
+}
+
+/// Get the indices of the tile containing the given position, or `None` if the position is not
+/// finite or lies outside the bounds of a HexArray with the given dimensions.
+pub(crate) fn pos_to_tile_within(
+    height: usize,
+    width: usize,
+    x: f64,
+    y: f64,
+) -> Option<(isize, isize)> {
+    let (left, right, bottom, top) = bounds(height, width);
+    // widen the bounds a little so that rounding them to f32 never excludes a tile
+    let inside = x >= left as f64 - 1.0
+        && x <= right as f64 + 1.0
+        && y >= bottom as f64 - 1.0
+        && y <= top as f64 + 1.0;
+    inside.then(|| pos_to_tile(x, y))
+}
+
+/// Round to the nearest integer, away from zero on ties.
+#[cfg(feature = "std")]
+fn round(x: f64) -> f64 {
+    x.round()
+}
+
+/// Round to the nearest integer, away from zero on ties.
+#[cfg(not(feature = "std"))]
+fn round(x: f64) -> f64 {
+    libm::round(x)
+}
+
+/// Get the left, right, bottom and top extents of the tiles of a HexArray.
+pub(crate) fn bounds(height: usize, width: usize) -> (f32, f32, f32, f32) {
+    const SQRT_3: f32 = 1.732050807568877293527446341505872367_f32;
+    const HALF_SQRT_3: f32 = 0.8660254037844386467637231707529361835_f32;
+    if height == 0 || width == 0 {
+        return (0.0, 0.0, 0.0, 0.0);
+    }
+    let right = (height - 1) as f32 * 1.5 + 1.0;
+    let mut top = (width - 1) as f32 * SQRT_3 + HALF_SQRT_3;
+    if height > 1 {
+        top += HALF_SQRT_3;
+    }
+    (-1.0, right, -HALF_SQRT_3, top)
+}
+
+/// Convert offset indices to axial coordinates.
+pub(crate) fn to_axial(x: isize, y: isize) -> (isize, isize) {
+    (x, y - x.div_euclid(2))
+}
+
+/// Convert axial coordinates to offset indices.
+pub(crate) fn from_axial(q: isize, r: isize) -> (isize, isize) {
+    (q, r + q.div_euclid(2))
+}
+
+/// Get the number of steps between two tiles given by offset indices.
+pub(crate) fn hex_distance(a: (isize, isize), b: (isize, isize)) -> usize {
+    let (aq, ar) = to_axial(a.0, a.1);
+    let (bq, br) = to_axial(b.0, b.1);
+    let (dq, dr) = (bq - aq, br - ar);
+    (dq.unsigned_abs() + dr.unsigned_abs() + (dq + dr).unsigned_abs()) / 2
+}
+
+/// Get the number of steps from the given, possibly out of bounds, tile to the farthest tile of a
+/// HexArray of the given dimensions, or 0 if it has no tiles.
+pub(crate) fn max_distance_within(center: (isize, isize), height: usize, width: usize) -> usize {
+    if height == 0 || width == 0 {
+        return 0;
+    }
+    let (bottom, top) = (0, width as isize - 1);
+    // the odd columns zigzag, so the last even column may hold the farthest tile too
+    let columns = [0, (height as isize - 2).max(0), height as isize - 1];
+    columns
+        .into_iter()
+        .flat_map(|x| [(x, bottom), (x, top)])
+        .map(|corner| hex_distance(center, corner))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Get the axial offsets of all tiles within the given distance, paired with their distance.
+pub(crate) fn axial_range(radius: usize) -> impl Iterator<Item = (isize, isize, usize)> {
+    let radius = radius as isize;
+    (-radius..=radius).flat_map(move |dq| {
+        let start = (-radius).max(-dq - radius);
+        let end = radius.min(-dq + radius);
+        (start..=end).map(move |dr| {
+            let distance = (dq.abs() + dr.abs() + (dq + dr).abs()) / 2;
+            (dq, dr, distance as usize)
+        })
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -184,26 +400,81 @@ mod tests {
         assert_eq!(hex_array.get(4, 4), None);
     }
 
+    // resize tests
+
+    #[test]
+    fn test_resize() {
+        let mut hex_array = HexArray::new(2, 3, 0);
+        hex_array.set(1, 2, 1);
+        hex_array.resize(3, 4, 2);
+        assert_eq!(hex_array.height(), 3);
+        assert_eq!(hex_array.width(), 4);
+        assert_eq!(hex_array.get(1, 2), Some(&1));
+        assert_eq!(hex_array.get(1, 3), Some(&2));
+        assert_eq!(hex_array.get(2, 0), Some(&2));
+        hex_array.resize(2, 2, 3);
+        assert_eq!(hex_array.tiles, vec![0, 0, 0, 0]);
+    }
+
     // position tests
 
     #[test]
     fn test_position_0_0() {
-        assert_eq!(HexArray::<i32>::position(0, 0), (0.0, 0.0));
+        assert_eq!(HexArray::<i32>::position(0, 0), Point::new(0.0, 0.0));
     }
 
     #[test]
     fn test_position_0_1() {
-        assert_eq!(HexArray::<i32>::position(0, 1), (0.0, 1.7320508));
+        assert_eq!(HexArray::<i32>::position(0, 1), Point::new(0.0, 1.7320508));
     }
 
     #[test]
     fn test_position_1_0() {
-        assert_eq!(HexArray::<i32>::position(1, 0), (1.5, 0.8660254));
+        assert_eq!(HexArray::<i32>::position(1, 0), Point::new(1.5, 0.8660254));
     }
 
     #[test]
     fn test_position_1_1() {
-        assert_eq!(HexArray::<i32>::position(1, 1), (1.5, 2.598076));
+        assert_eq!(HexArray::<i32>::position(1, 1), Point::new(1.5, 2.598076));
+    }
+
+    #[test]
+    fn test_position_f64() {
+        let position = HexArray::<i32>::position_f64(1, 1);
+        assert!((position.x - 1.5).abs() < 1e-12);
+        assert!((position.y - 2.598076211353316).abs() < 1e-12);
+        let far = HexArray::<i32>::position_f64(1_000_001, 0);
+        assert_eq!(far.x, 1_500_001.5);
+    }
+
+    // pos_to_tile tests
+
+    #[test]
+    fn test_pos_to_tile() {
+        for x in 0..5 {
+            for y in 0..5 {
+                let (px, py) = pos_f64(x, y);
+                assert_eq!(pos_to_tile(px, py), (x, y));
+                assert_eq!(pos_to_tile(px + 0.8, py + 0.1), (x, y));
+                assert_eq!(pos_to_tile(px - 0.4, py - 0.7), (x, y));
+            }
+        }
+        assert_eq!(pos_to_tile(-1.4, 0.5), (-1, 0));
+    }
+
+    // tile_at tests
+
+    #[test]
+    fn test_tile_at() {
+        let hex_array = HexArray::new(4, 4, 0);
+        assert_eq!(hex_array.tile_at(1.5, 0.8660254), Some((1, 0)));
+        assert_eq!(hex_array.tile_at(4.2, 5.0), Some((3, 2)));
+        assert_eq!(hex_array.tile_at(-1.2, 0.0), None);
+        assert_eq!(hex_array.tile_at(0.0, 7.0), None);
+        assert_eq!(hex_array.tile_at(f32::NAN, f32::NAN), None);
+        assert_eq!(hex_array.tile_at(f32::INFINITY, 0.0), None);
+        assert_eq!(hex_array.tile_at_f64(1e30, 1e40), None);
+        assert_eq!(HexArray::<i32>::new(0, 0, 0).tile_at(0.0, 0.0), None);
     }
 
     // adjacent tests
@@ -342,4 +613,11 @@ mod tests {
         let hex_array = HexArray::new(4, 4, 0);
         assert_adjacent(&hex_array.adjacent(3, 3), &vec![(2, 3), (3, 2)]);
     }
+
+    #[test]
+    fn test_adjacent_non_square() {
+        let hex_array = HexArray::new(2, 5, 0);
+        assert_adjacent(&hex_array.adjacent(1, 4), &vec![(0, 4), (1, 3)]);
+        assert_adjacent(&hex_array.adjacent(0, 4), &vec![(0, 3), (1, 3), (1, 4)]);
+    }
 }
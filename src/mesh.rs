@@ -0,0 +1,335 @@
+//! Build triangle meshes of the tiles of a HexArray for GPU rendering.
+//!
+//! Tiles lie in the xy plane with the geometry of `HexArray::position` and `HexArray::corners`,
+//! scaled by `MeshOptions::size`. Prisms are extruded along +z. Triangles are wound counter-clockwise
+//! when seen from outside of the mesh, and UVs map the bounding box of every top face onto the
+//! unit square with v pointing south.
+//!
+//! Vertices store plain arrays so that their layout does not depend on features; with the `glam`
+//! feature, `MeshVertex::position_vec3` and `MeshVertex::uv_vec2` return them as glam vectors.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::{pos, Direction, HexArray};
+
+const HALF_SQRT_3: f32 = 0.8660254037844386467637231707529361835_f32;
+
+/// The position and UV of a vertex.
+type FaceVertex = ((f32, f32, f32), (f32, f32));
+
+/// A vertex of a mesh, laid out to be uploaded as an interleaved vertex buffer.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(C)]
+pub struct MeshVertex<A> {
+    pub position: [f32; 3],
+    pub uv: [f32; 2],
+    /// The attribute of the tile the vertex belongs to.
+    pub attribute: A,
+}
+
+#[cfg(feature = "glam")]
+impl<A> MeshVertex<A> {
+    /// Get the position as a glam vector.
+    pub fn position_vec3(&self) -> glam::Vec3 {
+        glam::Vec3::from_array(self.position)
+    }
+
+    /// Get the UV as a glam vector.
+    pub fn uv_vec2(&self) -> glam::Vec2 {
+        glam::Vec2::from_array(self.uv)
+    }
+}
+
+/// A triangle mesh, with three indices into `vertices` per triangle.
+///
+/// Indices are `u32`, so building a mesh of more than `u32::MAX` vertices panics.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mesh<A> {
+    pub vertices: Vec<MeshVertex<A>>,
+    pub indices: Vec<u32>,
+}
+
+/// Options for building a mesh.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MeshOptions {
+    /// The distance from the center of a tile to its corners.
+    pub size: f32,
+    /// Share vertices between the triangles of a face, instead of giving every triangle its own
+    /// three vertices for flat shading.
+    pub shared_vertices: bool,
+    /// Only build the given tiles instead of the whole HexArray. Tiles outside of the HexArray are
+    /// skipped.
+    pub tiles: Option<Vec<(usize, usize)>>,
+}
+
+impl Default for MeshOptions {
+    fn default() -> Self {
+        MeshOptions {
+            size: 1.0,
+            shared_vertices: true,
+            tiles: None,
+        }
+    }
+}
+
+impl<A> Mesh<A>
+where
+    A: Clone,
+{
+    fn push_vertex(&mut self, (x, y, z): (f32, f32, f32), (u, v): (f32, f32), attribute: &A) {
+        self.vertices.push(MeshVertex {
+            position: [x, y, z],
+            uv: [u, v],
+            attribute: attribute.clone(),
+        });
+    }
+
+    /// Add a face made of the given vertices and triangles of indices into them.
+    fn push_face(
+        &mut self,
+        vertices: &[FaceVertex],
+        triangles: &[[usize; 3]],
+        shared_vertices: bool,
+        attribute: &A,
+    ) {
+        let added = match shared_vertices {
+            true => vertices.len(),
+            false => triangles.len() * 3,
+        };
+        let end = self.vertices.len() + added;
+        assert!(
+            u32::try_from(end).is_ok(),
+            "a mesh cannot have more than u32::MAX vertices"
+        );
+        // every index is below `end`, so none of the conversions truncate
+        let start = self.vertices.len() as u32;
+        if shared_vertices {
+            for (position, uv) in vertices {
+                self.push_vertex(*position, *uv, attribute);
+            }
+            for triangle in triangles {
+                self.indices
+                    .extend(triangle.iter().map(|index| start + *index as u32));
+            }
+        } else {
+            for (index, vertex) in triangles.iter().flatten().enumerate() {
+                let (position, uv) = vertices[*vertex];
+                self.push_vertex(position, uv, attribute);
+                self.indices.push(start + index as u32);
+            }
+        }
+    }
+}
+
+fn build<T, A, H, F>(
+    hex_array: &HexArray<T>,
+    options: &MeshOptions,
+    mut height: Option<H>,
+    mut attribute: F,
+) -> Mesh<A>
+where
+    A: Clone,
+    H: FnMut(&T) -> f32,
+    F: FnMut(&T) -> A,
+{
+    let tiles: Vec<(usize, usize)> = match &options.tiles {
+        Some(tiles) => tiles
+            .iter()
+            .copied()
+            .filter(|(x, y)| hex_array.get(*x, *y).is_some())
+            .collect(),
+        None => (0..hex_array.height)
+            .flat_map(|x| (0..hex_array.width).map(move |y| (x, y)))
+            .collect(),
+    };
+    let heights: Option<Vec<f32>> = height
+        .as_mut()
+        .map(|height| hex_array.tiles.iter().map(height).collect());
+    let tile_height = |x: usize, y: usize| {
+        heights
+            .as_ref()
+            .map_or(0.0, |heights| heights[x * hex_array.width + y])
+    };
+    let size = options.size;
+    let mut mesh = Mesh {
+        vertices: Vec::new(),
+        indices: Vec::new(),
+    };
+    for (x, y) in tiles {
+        let attribute = attribute(&hex_array.tiles[x * hex_array.width + y]);
+        let (cx, cy) = pos(x as isize, y as isize);
+        let z = tile_height(x, y) * size;
+        let corners = HexArray::<()>::corner_pos(x, y);
+        let mut face = vec![((cx * size, cy * size, z), (0.5, 0.5))];
+        face.extend(corners.iter().map(|(px, py)| {
+            let uv = ((px - cx + 1.0) / 2.0, (1.0 - (py - cy) / HALF_SQRT_3) / 2.0);
+            ((px * size, py * size, z), uv)
+        }));
+        let triangles: Vec<[usize; 3]> = (0..6).map(|i| [0, (i + 1) % 6 + 1, i + 1]).collect();
+        mesh.push_face(&face, &triangles, options.shared_vertices, &attribute);
+        if heights.is_none() {
+            continue;
+        }
+        for i in 0..6 {
+            // the edge between corners i and i + 1 faces direction i + 1
+            let (nx, ny) = Direction::from_index(i + 1).step(x as isize, y as isize);
+            let bottom = match hex_array.in_bounds(nx, ny) {
+                Some((nx, ny)) => tile_height(nx, ny) * size,
+                None => 0.0,
+            };
+            if bottom >= z {
+                continue;
+            }
+            let (ax, ay) = corners[i];
+            let (bx, by) = corners[(i + 1) % 6];
+            let wall = [
+                ((ax * size, ay * size, bottom), (0.0, 1.0)),
+                ((ax * size, ay * size, z), (0.0, 0.0)),
+                ((bx * size, by * size, z), (1.0, 0.0)),
+                ((bx * size, by * size, bottom), (1.0, 1.0)),
+            ];
+            mesh.push_face(
+                &wall,
+                &[[0, 1, 2], [0, 2, 3]],
+                options.shared_vertices,
+                &attribute,
+            );
+        }
+    }
+    mesh
+}
+
+/// Build a flat mesh of the tiles of the HexArray, six triangles per tile, giving every vertex the
+/// attribute of its tile.
+pub fn flat<T, A, F>(hex_array: &HexArray<T>, options: &MeshOptions, attribute: F) -> Mesh<A>
+where
+    A: Clone,
+    F: FnMut(&T) -> A,
+{
+    build(hex_array, options, None::<fn(&T) -> f32>, attribute)
+}
+
+/// Build a mesh of hexagonal prisms extruded from z = 0 to the height of every tile, giving every
+/// vertex the attribute of its tile.
+///
+/// Heights are scaled by `MeshOptions::size` like the other coordinates. Side walls are only built
+/// where they are visible, above lower neighbors and the edges of the HexArray.
+pub fn prisms<T, A, H, F>(
+    hex_array: &HexArray<T>,
+    options: &MeshOptions,
+    height: H,
+    attribute: F,
+) -> Mesh<A>
+where
+    A: Clone,
+    H: FnMut(&T) -> f32,
+    F: FnMut(&T) -> A,
+{
+    build(hex_array, options, Some(height), attribute)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Get the normal of every triangle.
+    fn normals<A>(mesh: &Mesh<A>) -> Vec<[f32; 3]> {
+        mesh.indices
+            .chunks(3)
+            .map(|triangle| {
+                let [a, b, c] = [0, 1, 2].map(|i| mesh.vertices[triangle[i] as usize].position);
+                let u = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+                let v = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+                [
+                    u[1] * v[2] - u[2] * v[1],
+                    u[2] * v[0] - u[0] * v[2],
+                    u[0] * v[1] - u[1] * v[0],
+                ]
+            })
+            .collect()
+    }
+
+    // flat tests
+
+    #[test]
+    fn test_flat() {
+        let hex_array = HexArray::new(2, 3, 1u8);
+        let mesh = flat(&hex_array, &MeshOptions::default(), |tile| *tile);
+        assert_eq!(mesh.vertices.len(), 6 * 7);
+        assert_eq!(mesh.indices.len(), 6 * 18);
+        assert!(mesh.vertices.iter().all(|vertex| vertex.attribute == 1));
+        assert!(normals(&mesh).iter().all(|normal| normal[2] > 0.0));
+    }
+
+    #[test]
+    fn test_flat_uvs() {
+        let hex_array = HexArray::new(1, 1, ());
+        let options = MeshOptions {
+            size: 2.0,
+            ..MeshOptions::default()
+        };
+        let mesh = flat(&hex_array, &options, |_| ());
+        let uvs: Vec<[f32; 2]> = mesh.vertices.iter().map(|vertex| vertex.uv).collect();
+        assert_eq!(uvs[0], [0.5, 0.5]);
+        // corner 1 is east and corner 5 is north west
+        assert_eq!(uvs[2], [1.0, 0.5]);
+        assert!((uvs[6][0] - 0.25).abs() < 1e-6 && uvs[6][1].abs() < 1e-6);
+        assert_eq!(mesh.vertices[2].position, [2.0, 0.0, 0.0]);
+    }
+
+    #[cfg(feature = "glam")]
+    #[test]
+    fn test_flat_glam() {
+        let hex_array = HexArray::new(1, 1, ());
+        let mesh = flat(&hex_array, &MeshOptions::default(), |_| ());
+        assert_eq!(
+            mesh.vertices[2].position_vec3(),
+            glam::Vec3::new(1.0, 0.0, 0.0)
+        );
+        assert_eq!(mesh.vertices[2].uv_vec2(), glam::Vec2::new(1.0, 0.5));
+    }
+
+    #[test]
+    fn test_flat_unshared() {
+        let hex_array = HexArray::new(2, 2, ());
+        let options = MeshOptions {
+            shared_vertices: false,
+            tiles: Some(vec![(0, 0), (1, 1), (5, 5)]),
+            ..MeshOptions::default()
+        };
+        let mesh = flat(&hex_array, &options, |_| ());
+        assert_eq!(mesh.vertices.len(), 2 * 18);
+        assert_eq!(mesh.indices, (0..36).collect::<Vec<u32>>());
+    }
+
+    // prisms tests
+
+    #[test]
+    fn test_prisms_single() {
+        let hex_array = HexArray::new(1, 1, 2.0f32);
+        let mesh = prisms(&hex_array, &MeshOptions::default(), |tile| *tile, |_| ());
+        assert_eq!(mesh.vertices.len(), 7 + 6 * 4);
+        assert_eq!(mesh.indices.len(), 18 + 6 * 6);
+        let normals = normals(&mesh);
+        assert!(normals[..6].iter().all(|normal| normal[2] > 0.0));
+        // walls face away from the center of the tile
+        for (triangle, normal) in mesh.indices.chunks(3).zip(&normals).skip(6) {
+            let a = mesh.vertices[triangle[0] as usize].position;
+            assert!(normal[2].abs() < 1e-6);
+            assert!(a[0] * normal[0] + a[1] * normal[1] > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_prisms_hidden_walls() {
+        let mut hex_array = HexArray::new(2, 1, 1.0f32);
+        hex_array.set(1, 0, 0.0);
+        let mesh = prisms(&hex_array, &MeshOptions::default(), |tile| *tile, |_| ());
+        // every wall of the raised tile is visible, the flat tile has none
+        assert_eq!(mesh.vertices.len(), 2 * 7 + 6 * 4);
+        hex_array.set(1, 0, 1.0);
+        let mesh = prisms(&hex_array, &MeshOptions::default(), |tile| *tile, |_| ());
+        assert_eq!(mesh.vertices.len(), 2 * 7 + 10 * 4);
+    }
+}
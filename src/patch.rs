@@ -0,0 +1,189 @@
+use alloc::vec::Vec;
+
+use crate::HexArray;
+
+/// The changes turning one HexArray into another, created with `HexArray::diff`.
+///
+/// A patch records the dimensions of both HexArrays and the tiles of the second one that differ
+/// from the first, including every tile added by growing the HexArray.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct HexPatch<T> {
+    source: (usize, usize),
+    target: (usize, usize),
+    tiles: Vec<((usize, usize), T)>,
+}
+
+impl<T> HexPatch<T> {
+    /// Create a new HexPatch from the dimensions before and after and the changed tiles.
+    ///
+    /// Returns `None` unless the tiles are inside the target dimensions, sorted by index without
+    /// duplicates, and include every tile outside the source dimensions.
+    pub fn new(
+        source: (usize, usize),
+        target: (usize, usize),
+        tiles: Vec<((usize, usize), T)>,
+    ) -> Option<Self> {
+        let (height, width) = target;
+        let size = height.checked_mul(width)?;
+        // cannot overflow, since it is at most the size of the target
+        let kept = height.min(source.0) * width.min(source.1);
+        let mut added = 0;
+        let mut previous = None;
+        for &((x, y), _) in &tiles {
+            if x >= height || y >= width || previous >= Some((x, y)) {
+                return None;
+            }
+            previous = Some((x, y));
+            if x >= source.0 || y >= source.1 {
+                added += 1;
+            }
+        }
+        if size - kept != added {
+            return None;
+        }
+        Some(HexPatch {
+            source,
+            target,
+            tiles,
+        })
+    }
+
+    /// Get the height and width of the HexArray the patch applies to.
+    pub fn source(&self) -> (usize, usize) {
+        self.source
+    }
+
+    /// Get the height and width of the HexArray after applying the patch.
+    pub fn target(&self) -> (usize, usize) {
+        self.target
+    }
+
+    /// Get the indices and new values of the changed tiles, sorted by index.
+    pub fn tiles(&self) -> &[((usize, usize), T)] {
+        &self.tiles
+    }
+
+    /// Check whether applying the patch changes nothing.
+    pub fn is_empty(&self) -> bool {
+        self.source == self.target && self.tiles.is_empty()
+    }
+}
+
+impl<T> HexArray<T>
+where
+    T: Clone + PartialEq,
+{
+    /// Get the patch turning this HexArray into the other one.
+    pub fn diff(&self, other: &HexArray<T>) -> HexPatch<T> {
+        let mut tiles = Vec::new();
+        for x in 0..other.height {
+            for y in 0..other.width {
+                let tile = &other.tiles[x * other.width + y];
+                if self.get(x, y) != Some(tile) {
+                    tiles.push(((x, y), tile.clone()));
+                }
+            }
+        }
+        HexPatch {
+            source: (self.height, self.width),
+            target: (other.height, other.width),
+            tiles,
+        }
+    }
+}
+
+impl<T> HexArray<T> {
+    /// Apply a patch created by `diff`, if the dimensions match those it was created from.
+    pub fn apply(&mut self, patch: HexPatch<T>) -> Option<()> {
+        if (self.height, self.width) != patch.source {
+            return None;
+        }
+        let (height, width) = patch.target;
+        if patch.source == patch.target {
+            for ((x, y), tile) in patch.tiles {
+                self.tiles[x * width + y] = tile;
+            }
+            return Some(());
+        }
+        let mut old: Vec<_> = core::mem::take(&mut self.tiles)
+            .into_iter()
+            .map(Some)
+            .collect();
+        let mut changes = patch.tiles.into_iter().peekable();
+        let mut tiles = Vec::with_capacity(height * width);
+        for x in 0..height {
+            for y in 0..width {
+                let tile = match changes.next_if(|(index, _)| *index == (x, y)) {
+                    Some((_, tile)) => tile,
+                    None => old[x * self.width + y]
+                        .take()
+                        .expect("patch covers every added tile"),
+                };
+                tiles.push(tile);
+            }
+        }
+        self.height = height;
+        self.width = width;
+        self.tiles = tiles;
+        Some(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // diff tests
+
+    #[test]
+    fn test_diff() {
+        let before = HexArray::new(3, 3, 0);
+        let mut after = before.clone();
+        after.set(2, 0, 4);
+        after.set(0, 1, 5);
+        let patch = before.diff(&after);
+        assert_eq!(patch.source(), (3, 3));
+        assert_eq!(patch.target(), (3, 3));
+        assert_eq!(patch.tiles(), [((0, 1), 5), ((2, 0), 4)]);
+        assert!(before.diff(&before).is_empty());
+    }
+
+    #[test]
+    fn test_diff_resize() {
+        let before = HexArray::new(2, 3, 0);
+        let mut after = HexArray::new(3, 2, 0);
+        after.set(0, 0, 1);
+        let patch = before.diff(&after);
+        assert_eq!(patch.tiles(), [((0, 0), 1), ((2, 0), 0), ((2, 1), 0)]);
+        assert!(!HexArray::new(2, 2, 0)
+            .diff(&HexArray::new(1, 2, 0))
+            .is_empty());
+    }
+
+    // apply tests
+
+    #[test]
+    fn test_apply() {
+        let before = HexArray::new(4, 3, 0);
+        let mut after = HexArray::new(2, 5, 0);
+        after.set(1, 1, 2);
+        after.set(1, 4, 3);
+        for (from, to) in [(&before, &after), (&after, &before), (&before, &before)] {
+            let mut patched = from.clone();
+            assert_eq!(patched.apply(from.diff(to)), Some(()));
+            assert!(patched == *to);
+        }
+        let mut wrong = HexArray::new(4, 4, 0);
+        assert_eq!(wrong.apply(before.diff(&after)), None);
+    }
+
+    #[test]
+    fn test_new() {
+        assert!(HexPatch::new((1, 1), (1, 2), vec![((0, 1), 0)]).is_some());
+        assert!(HexPatch::new((1, 1), (1, 2), vec![((0, 0), 0)]).is_none());
+        assert!(HexPatch::new((1, 2), (1, 2), vec![((0, 1), 0), ((0, 0), 0)]).is_none());
+        assert!(HexPatch::new((1, 2), (1, 2), vec![((0, 2), 0)]).is_none());
+        let huge = 1 << (usize::BITS / 2);
+        assert!(HexPatch::<u8>::new((huge, huge), (huge, huge), vec![]).is_none());
+    }
+}
@@ -0,0 +1,161 @@
+/// A position in the plane of the tiles, with north pointing towards +y.
+///
+/// Positions are `Point<f32>` by default; `HexArray::position_f64` and `HexArray::tile_at_f64`
+/// work with `Point<f64>` for large world coordinates. Conversions to the point and vector types
+/// of glam, mint and nalgebra are available behind the features of the same names.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Point<S = f32> {
+    pub x: S,
+    pub y: S,
+}
+
+impl<S> Point<S> {
+    /// Create a new Point with the given coordinates.
+    pub const fn new(x: S, y: S) -> Self {
+        Point { x, y }
+    }
+}
+
+impl<S> From<(S, S)> for Point<S> {
+    fn from((x, y): (S, S)) -> Self {
+        Point { x, y }
+    }
+}
+
+impl<S> From<Point<S>> for (S, S) {
+    fn from(point: Point<S>) -> Self {
+        (point.x, point.y)
+    }
+}
+
+impl<S> From<[S; 2]> for Point<S> {
+    fn from([x, y]: [S; 2]) -> Self {
+        Point { x, y }
+    }
+}
+
+impl<S> From<Point<S>> for [S; 2] {
+    fn from(point: Point<S>) -> Self {
+        [point.x, point.y]
+    }
+}
+
+impl From<Point<f32>> for Point<f64> {
+    fn from(point: Point<f32>) -> Self {
+        Point::new(point.x as f64, point.y as f64)
+    }
+}
+
+#[cfg(feature = "glam")]
+impl From<glam::Vec2> for Point<f32> {
+    fn from(vec: glam::Vec2) -> Self {
+        Point::new(vec.x, vec.y)
+    }
+}
+
+#[cfg(feature = "glam")]
+impl From<Point<f32>> for glam::Vec2 {
+    fn from(point: Point<f32>) -> Self {
+        glam::Vec2::new(point.x, point.y)
+    }
+}
+
+#[cfg(feature = "glam")]
+impl From<glam::DVec2> for Point<f64> {
+    fn from(vec: glam::DVec2) -> Self {
+        Point::new(vec.x, vec.y)
+    }
+}
+
+#[cfg(feature = "glam")]
+impl From<Point<f64>> for glam::DVec2 {
+    fn from(point: Point<f64>) -> Self {
+        glam::DVec2::new(point.x, point.y)
+    }
+}
+
+#[cfg(feature = "mint")]
+impl<S> From<mint::Point2<S>> for Point<S> {
+    fn from(point: mint::Point2<S>) -> Self {
+        Point::new(point.x, point.y)
+    }
+}
+
+#[cfg(feature = "mint")]
+impl<S> From<Point<S>> for mint::Point2<S> {
+    fn from(point: Point<S>) -> Self {
+        mint::Point2 {
+            x: point.x,
+            y: point.y,
+        }
+    }
+}
+
+#[cfg(feature = "mint")]
+impl<S> mint::IntoMint for Point<S> {
+    type MintType = mint::Point2<S>;
+}
+
+#[cfg(feature = "nalgebra")]
+impl<S> From<nalgebra::Point2<S>> for Point<S>
+where
+    S: nalgebra::Scalar,
+{
+    fn from(point: nalgebra::Point2<S>) -> Self {
+        let [x, y] = <[S; 2]>::from(point.coords);
+        Point::new(x, y)
+    }
+}
+
+#[cfg(feature = "nalgebra")]
+impl<S> From<Point<S>> for nalgebra::Point2<S>
+where
+    S: nalgebra::Scalar,
+{
+    fn from(point: Point<S>) -> Self {
+        nalgebra::Point2::new(point.x, point.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // From tests
+
+    #[test]
+    fn test_from_tuple() {
+        let point = Point::from((1.5f32, -2.0));
+        assert_eq!(point, Point::new(1.5, -2.0));
+        assert_eq!(<(f32, f32)>::from(point), (1.5, -2.0));
+        assert_eq!(<[f32; 2]>::from(point), [1.5, -2.0]);
+        assert_eq!(Point::<f64>::from(point), Point::new(1.5f64, -2.0));
+    }
+
+    #[cfg(feature = "glam")]
+    #[test]
+    fn test_from_glam() {
+        let point = Point::from(glam::Vec2::new(1.0, 2.0));
+        assert_eq!(glam::Vec2::from(point), glam::Vec2::new(1.0, 2.0));
+        let point = Point::from(glam::DVec2::new(1.0, 2.0));
+        assert_eq!(glam::DVec2::from(point), glam::DVec2::new(1.0, 2.0));
+    }
+
+    #[cfg(feature = "mint")]
+    #[test]
+    fn test_from_mint() {
+        let point = Point::from(mint::Point2 { x: 1.0f64, y: 2.0 });
+        assert_eq!(mint::Point2::from(point), mint::Point2 { x: 1.0, y: 2.0 });
+    }
+
+    #[cfg(feature = "nalgebra")]
+    #[test]
+    fn test_from_nalgebra() {
+        let point = Point::from(nalgebra::Point2::new(1.0f32, 2.0));
+        assert_eq!(point, Point::new(1.0, 2.0));
+        assert_eq!(
+            nalgebra::Point2::from(point),
+            nalgebra::Point2::new(1.0, 2.0)
+        );
+    }
+}
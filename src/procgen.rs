@@ -0,0 +1,217 @@
+//! Procedural generation of terrain on HexArrays.
+//!
+//! Noise is sampled at the position of every tile, so maps look the same regardless of the
+//! layout of the tiles. Every function is deterministic: the same noise seed and parameters
+//! always produce the same map.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use noise::{Fbm, NoiseFn, Perlin};
+
+use crate::{Border, HexArray};
+
+/// Sample the noise function at the position of every tile, scaled by the given factor.
+pub fn sample<N>(noise: &N, height: usize, width: usize, scale: f64) -> HexArray<f32>
+where
+    N: NoiseFn<f64, 2>,
+{
+    let mut result = HexArray::new(height, width, 0.0);
+    for x in 0..height {
+        for y in 0..width {
+            let position = HexArray::<f32>::position_f64(x, y);
+            let value = noise.get([position.x * scale, position.y * scale]);
+            result.tiles[x * width + y] = value as f32;
+        }
+    }
+    result
+}
+
+/// Sample fractal Perlin noise with the given seed at the position of every tile.
+///
+/// Values lie roughly between -1 and 1; a scale around 0.05 gives features spanning a few dozen
+/// tiles.
+pub fn fbm(seed: u32, height: usize, width: usize, scale: f64) -> HexArray<f32> {
+    sample(&Fbm::<Perlin>::new(seed), height, width, scale)
+}
+
+/// Average every tile with its adjacent tiles the given number of times.
+pub fn smooth(heights: &HexArray<f32>, iterations: usize) -> HexArray<f32> {
+    let mut result = heights.clone();
+    for _ in 0..iterations {
+        result = result.convolve(1, &[1.0 / 7.0, 1.0 / 7.0], Border::Clamp);
+    }
+    result
+}
+
+/// Classify every tile by the number of thresholds at or below its value.
+///
+/// `classes` must have one more entry than `thresholds`, which must be sorted in ascending order:
+/// tiles below the first threshold get the first class and tiles at or above the last threshold
+/// the last class. Returns `None` if the lengths do not match.
+pub fn classify<C>(
+    heights: &HexArray<f32>,
+    thresholds: &[f32],
+    classes: &[C],
+) -> Option<HexArray<C>>
+where
+    C: Clone,
+{
+    if classes.len() != thresholds.len() + 1 {
+        return None;
+    }
+    Some(heights.map(|height| {
+        let index = thresholds.partition_point(|threshold| threshold <= height);
+        classes[index].clone()
+    }))
+}
+
+/// Get the lowest adjacent tile, if it is lower than the given tile.
+fn lowest_neighbor(heights: &HexArray<f32>, x: usize, y: usize) -> Option<(usize, usize)> {
+    let mut lowest = heights.tiles[x * heights.width + y];
+    let mut result = None;
+    for (nx, ny) in heights.adjacent(x, y) {
+        let height = heights.tiles[nx * heights.width + ny];
+        if height < lowest {
+            lowest = height;
+            result = Some((nx, ny));
+        }
+    }
+    result
+}
+
+/// Simulate water flowing downhill from every tile, carrying material from steep slopes to pits.
+///
+/// One droplet starts on every tile in index order, `iterations` times over. At every step a
+/// droplet moves to the lowest adjacent tile, taking `strength` times the height difference from
+/// the tile it leaves, but never so much that the tile drops below the next one. It deposits its
+/// load when it reaches a tile without lower neighbors or after `max_steps` steps. The total height
+/// of the map is preserved.
+pub fn erode(heights: &mut HexArray<f32>, iterations: usize, max_steps: usize, strength: f32) {
+    let strength = strength.clamp(0.0, 1.0);
+    for _ in 0..iterations {
+        for x in 0..heights.height {
+            for y in 0..heights.width {
+                let (mut x, mut y) = (x, y);
+                let mut sediment = 0.0;
+                for _ in 0..max_steps {
+                    let Some((nx, ny)) = lowest_neighbor(heights, x, y) else {
+                        break;
+                    };
+                    let index = x * heights.width + y;
+                    let drop = heights.tiles[index] - heights.tiles[nx * heights.width + ny];
+                    let eroded = drop * strength * 0.5;
+                    heights.tiles[index] -= eroded;
+                    sediment += eroded;
+                    (x, y) = (nx, ny);
+                }
+                heights.tiles[x * heights.width + y] += sediment;
+            }
+        }
+    }
+}
+
+/// Trace the path of a river from the given tile downhill until it reaches a tile without lower
+/// neighbors, including both ends.
+pub fn trace_river(heights: &HexArray<f32>, x: usize, y: usize) -> Option<Vec<(usize, usize)>> {
+    heights.get(x, y)?;
+    let mut path = vec![(x, y)];
+    let (mut x, mut y) = (x, y);
+    while let Some((nx, ny)) = lowest_neighbor(heights, x, y) {
+        path.push((nx, ny));
+        (x, y) = (nx, ny);
+    }
+    Some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A slope rising towards larger x with a pit in the middle.
+    fn slope() -> HexArray<f32> {
+        let mut heights = HexArray::new(6, 6, 0.0);
+        for x in 0..6 {
+            for y in 0..6 {
+                heights.set(x, y, x as f32 + y as f32 * 0.1);
+            }
+        }
+        heights.set(3, 3, -1.0);
+        heights
+    }
+
+    // sample tests
+
+    #[test]
+    fn test_fbm_deterministic() {
+        let a = fbm(7, 8, 8, 0.1);
+        assert!(a == fbm(7, 8, 8, 0.1));
+        assert!(a != fbm(8, 8, 8, 0.1));
+        assert!(a.fold(true, |ok, height| ok && (-2.0..=2.0).contains(height)));
+    }
+
+    #[test]
+    fn test_sample_position() {
+        let noise = noise::Checkerboard::default();
+        let samples = sample(&noise, 2, 2, 1.0);
+        let expected = noise.get([1.5, 3.0f64.sqrt() * 1.5]) as f32;
+        assert_eq!(samples.get(1, 1), Some(&expected));
+    }
+
+    // smooth tests
+
+    #[test]
+    fn test_smooth() {
+        let mut heights = HexArray::new(5, 5, 0.0);
+        heights.set(2, 2, 7.0);
+        let smoothed = smooth(&heights, 1);
+        assert_eq!(smoothed.get(2, 2), Some(&1.0));
+        assert_eq!(smoothed.get(2, 3), Some(&1.0));
+        assert_eq!(smoothed.get(0, 0), Some(&0.0));
+        assert!(smooth(&heights, 0) == heights);
+    }
+
+    // classify tests
+
+    #[test]
+    fn test_classify() {
+        let heights = slope();
+        let terrain = classify(&heights, &[0.0, 2.0, 4.5], &['~', '.', '^', '#']).unwrap();
+        assert_eq!(terrain.get(3, 3), Some(&'~'));
+        assert_eq!(terrain.get(0, 0), Some(&'.'));
+        assert_eq!(terrain.get(2, 0), Some(&'^'));
+        assert_eq!(terrain.get(5, 5), Some(&'#'));
+        assert!(classify(&heights, &[0.0], &['~']).is_none());
+    }
+
+    // erode tests
+
+    #[test]
+    fn test_erode() {
+        let mut heights = slope();
+        let total = heights.fold(0.0, |sum, height| sum + height);
+        erode(&mut heights, 2, 16, 0.5);
+        let eroded = heights.fold(0.0, |sum, height| sum + height);
+        assert!((total - eroded).abs() < 1e-3);
+        assert!(heights.get(3, 3) > Some(&-1.0));
+        assert!(heights.get(5, 5) < Some(&5.5));
+        let mut again = slope();
+        erode(&mut again, 2, 16, 0.5);
+        assert!(again == heights);
+    }
+
+    // trace_river tests
+
+    #[test]
+    fn test_trace_river() {
+        let heights = slope();
+        let river = trace_river(&heights, 5, 3).unwrap();
+        assert_eq!(river.first(), Some(&(5, 3)));
+        assert_eq!(river.last(), Some(&(3, 3)));
+        for pair in river.windows(2) {
+            assert!(heights.get(pair[1].0, pair[1].1) < heights.get(pair[0].0, pair[0].1));
+        }
+        assert_eq!(trace_river(&heights, 0, 0), Some(vec![(0, 0)]));
+        assert_eq!(trace_river(&heights, 6, 0), None);
+    }
+}
@@ -0,0 +1,285 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use rand::{Rng, RngExt};
+
+use crate::{
+    axial_range, from_axial, hex_distance, max_distance_within, to_axial, HexArray, HexBitArray,
+};
+
+impl<T> HexArray<T> {
+    /// Get the indices of the tiles matching the predicate.
+    fn matching<F>(&self, mut predicate: F) -> Vec<(usize, usize)>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let mut tiles = Vec::new();
+        for x in 0..self.height {
+            for y in 0..self.width {
+                if predicate(&self.tiles[x * self.width + y]) {
+                    tiles.push((x, y));
+                }
+            }
+        }
+        tiles
+    }
+
+    /// Get the indices of a random tile matching the predicate, or `None` if no tile matches.
+    pub fn random_tile<F, R>(&self, mut predicate: F, rng: &mut R) -> Option<(usize, usize)>
+    where
+        F: FnMut(&T) -> bool,
+        R: Rng + ?Sized,
+    {
+        let mut result = None;
+        let mut count = 0;
+        for (index, tile) in self.tiles.iter().enumerate() {
+            if predicate(tile) {
+                count += 1;
+                if rng.random_range(0..count) == 0 {
+                    result = Some(index);
+                }
+            }
+        }
+        result.map(|index| (index / self.width, index % self.width))
+    }
+
+    /// Get the indices of a random tile, chosen with probability proportional to the weight the
+    /// closure gives it.
+    ///
+    /// Tiles without a positive, finite weight are never chosen. Returns `None` if no tile has one.
+    pub fn random_tile_weighted<F, R>(&self, mut weight: F, rng: &mut R) -> Option<(usize, usize)>
+    where
+        F: FnMut(&T) -> f64,
+        R: Rng + ?Sized,
+    {
+        let mut result = None;
+        let mut total = 0.0;
+        // halved whenever the total would overflow, keeping the ratios of the weights
+        let mut scale = 1.0;
+        for (index, tile) in self.tiles.iter().enumerate() {
+            let weight = weight(tile);
+            if !(weight > 0.0 && weight.is_finite()) {
+                continue;
+            }
+            let mut weight = weight * scale;
+            if total + weight == f64::INFINITY {
+                total *= 0.5;
+                weight *= 0.5;
+                scale *= 0.5;
+            }
+            if weight == 0.0 {
+                continue;
+            }
+            total += weight;
+            if rng.random_range(0.0..total) < weight {
+                result = Some(index);
+            }
+        }
+        result.map(|index| (index / self.width, index % self.width))
+    }
+
+    /// Get the indices of up to `count` random tiles matching the predicate, each at least
+    /// `min_distance` steps away from the others.
+    ///
+    /// Tiles are tried in random order, so fewer tiles are returned only when every matching tile
+    /// is too close to one already chosen.
+    pub fn poisson_disc<F, R>(
+        &self,
+        count: usize,
+        min_distance: usize,
+        predicate: F,
+        rng: &mut R,
+    ) -> Vec<(usize, usize)>
+    where
+        F: FnMut(&T) -> bool,
+        R: Rng + ?Sized,
+    {
+        let mut candidates = self.matching(predicate);
+        let mut blocked = HexBitArray::new(self.height, self.width, false);
+        let mut result = Vec::new();
+        for i in 0..candidates.len() {
+            if result.len() == count {
+                break;
+            }
+            let j = rng.random_range(i..candidates.len());
+            candidates.swap(i, j);
+            let (x, y) = candidates[i];
+            if blocked.get(x, y) != Some(false) {
+                continue;
+            }
+            result.push((x, y));
+            let (x, y) = (x as isize, y as isize);
+            let radius = min_distance.saturating_sub(1).min(max_distance_within(
+                (x, y),
+                self.height,
+                self.width,
+            ));
+            let (q, r) = to_axial(x, y);
+            for (dq, dr, _) in axial_range(radius) {
+                let (nx, ny) = from_axial(q + dq, r + dr);
+                if let Some((nx, ny)) = self.in_bounds(nx, ny) {
+                    blocked.set(nx, ny, true);
+                }
+            }
+        }
+        result
+    }
+
+    /// Get the indices of up to `count` tiles matching the predicate, spread out by starting at a
+    /// random tile and repeatedly adding the tile farthest from those already chosen.
+    pub fn farthest_points<F, R>(
+        &self,
+        count: usize,
+        predicate: F,
+        rng: &mut R,
+    ) -> Vec<(usize, usize)>
+    where
+        F: FnMut(&T) -> bool,
+        R: Rng + ?Sized,
+    {
+        let candidates = self.matching(predicate);
+        if candidates.is_empty() || count == 0 {
+            return Vec::new();
+        }
+        let mut distances = vec![usize::MAX; candidates.len()];
+        let mut next = rng.random_range(0..candidates.len());
+        let mut result = Vec::new();
+        loop {
+            let (x, y) = candidates[next];
+            result.push((x, y));
+            if result.len() == count {
+                break;
+            }
+            let mut farthest = 0;
+            for (i, &(cx, cy)) in candidates.iter().enumerate() {
+                let distance = hex_distance((x as isize, y as isize), (cx as isize, cy as isize));
+                distances[i] = distances[i].min(distance);
+                if distances[i] > farthest {
+                    farthest = distances[i];
+                    next = i;
+                }
+            }
+            if farthest == 0 {
+                break;
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+
+    fn assert_spread(tiles: &[(usize, usize)], min_distance: usize) {
+        for (i, &a) in tiles.iter().enumerate() {
+            for &b in &tiles[i + 1..] {
+                let distance =
+                    hex_distance((a.0 as isize, a.1 as isize), (b.0 as isize, b.1 as isize));
+                assert!(distance >= min_distance, "{a:?} {b:?}");
+            }
+        }
+    }
+
+    // random_tile tests
+
+    #[test]
+    fn test_random_tile() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut hex_array = HexArray::new(4, 4, 0u8);
+        assert_eq!(hex_array.random_tile(|tile| *tile == 1, &mut rng), None);
+        hex_array.set(1, 2, 1);
+        hex_array.set(3, 0, 1);
+        let mut seen = [false; 2];
+        for _ in 0..32 {
+            match hex_array.random_tile(|tile| *tile == 1, &mut rng) {
+                Some((1, 2)) => seen[0] = true,
+                Some((3, 0)) => seen[1] = true,
+                other => panic!("{other:?}"),
+            }
+        }
+        assert_eq!(seen, [true, true]);
+    }
+
+    #[test]
+    fn test_random_tile_weighted() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut hex_array = HexArray::new(3, 3, 0.0);
+        assert_eq!(hex_array.random_tile_weighted(|tile| *tile, &mut rng), None);
+        hex_array.set(0, 1, 1.0);
+        hex_array.set(2, 2, 3.0);
+        hex_array.set(1, 1, f64::NAN);
+        let mut counts = [0; 2];
+        for _ in 0..400 {
+            match hex_array.random_tile_weighted(|tile| *tile, &mut rng) {
+                Some((0, 1)) => counts[0] += 1,
+                Some((2, 2)) => counts[1] += 1,
+                other => panic!("{other:?}"),
+            }
+        }
+        assert!(counts[1] > counts[0] * 2);
+        let huge = HexArray::new(2, 2, f64::MAX);
+        assert!(huge.random_tile_weighted(|tile| *tile, &mut rng).is_some());
+        let mut mixed = HexArray::new(1, 3, f64::MAX);
+        mixed.set(0, 1, 1.0);
+        for _ in 0..32 {
+            assert_ne!(
+                mixed.random_tile_weighted(|tile| *tile, &mut rng),
+                Some((0, 1))
+            );
+        }
+    }
+
+    // poisson_disc tests
+
+    #[test]
+    fn test_poisson_disc() {
+        let mut hex_array = HexArray::new(12, 12, true);
+        hex_array.fill_range((6, 6), 2, false);
+        let tiles =
+            hex_array.poisson_disc(usize::MAX, 3, |tile| *tile, &mut SmallRng::seed_from_u64(5));
+        assert_spread(&tiles, 3);
+        for x in 0..12 {
+            for y in 0..12 {
+                let distance = tiles
+                    .iter()
+                    .map(|&(tx, ty)| hex_distance((x, y), (tx as isize, ty as isize)))
+                    .min();
+                let matches = hex_array.get(x as usize, y as usize) == Some(&true);
+                assert!(!matches || distance < Some(3), "({x}, {y})");
+            }
+        }
+        assert!(tiles
+            .iter()
+            .all(|&(x, y)| hex_array.get(x, y) == Some(&true)));
+        let again =
+            hex_array.poisson_disc(usize::MAX, 3, |tile| *tile, &mut SmallRng::seed_from_u64(5));
+        assert_eq!(again, tiles);
+        let few = hex_array.poisson_disc(2, 3, |tile| *tile, &mut SmallRng::seed_from_u64(5));
+        assert_eq!(few.len(), 2);
+        let far = HexArray::new(6, 6, ()).poisson_disc(
+            usize::MAX,
+            usize::MAX,
+            |_| true,
+            &mut SmallRng::seed_from_u64(5),
+        );
+        assert_eq!(far.len(), 1);
+    }
+
+    // farthest_points tests
+
+    #[test]
+    fn test_farthest_points() {
+        let hex_array = HexArray::new(1, 9, ());
+        let mut rng = SmallRng::seed_from_u64(2);
+        let tiles = hex_array.farthest_points(3, |_| true, &mut rng);
+        assert_eq!(tiles.len(), 3);
+        assert!(tiles.iter().any(|&(_, y)| y == 0 || y == 8));
+        assert_spread(&tiles, 2);
+        let all = hex_array.farthest_points(20, |_| true, &mut rng);
+        assert_eq!(all.len(), 9);
+        assert!(hex_array.farthest_points(3, |_| false, &mut rng).is_empty());
+    }
+}
@@ -0,0 +1,121 @@
+use image::{Rgba, RgbaImage};
+
+use crate::{bounds, pos, HexArray};
+
+impl<T> HexArray<T> {
+    /// Rasterize the HexArray into an image, coloring every tile with the given function.
+    ///
+    /// `hex_pixel_size` is the distance in pixels from the center of a tile to its corners.
+    /// North points up in the image and pixels outside of every tile are transparent.
+    pub fn to_image<F>(&self, hex_pixel_size: f32, mut color_fn: F) -> RgbaImage
+    where
+        F: FnMut(&T) -> Rgba<u8>,
+    {
+        let (left, right, bottom, top) = bounds(self.height, self.width);
+        let image_width = ((right - left) * hex_pixel_size).ceil() as u32;
+        let image_height = ((top - bottom) * hex_pixel_size).ceil() as u32;
+        let colors: Vec<Rgba<u8>> = self.tiles.iter().map(&mut color_fn).collect();
+        RgbaImage::from_fn(image_width, image_height, |i, j| {
+            let x = left + (i as f32 + 0.5) / hex_pixel_size;
+            let y = top - (j as f32 + 0.5) / hex_pixel_size;
+            match self.tile_at(x, y) {
+                Some((x, y)) => colors[x * self.width + y],
+                None => Rgba([0, 0, 0, 0]),
+            }
+        })
+    }
+
+    /// Create a HexArray by sampling the pixel at the center of every tile of an image.
+    ///
+    /// The image is expected to have the layout produced by `to_image` with the same
+    /// `hex_pixel_size`; centers outside of the image sample the nearest pixel.
+    pub fn from_image<F>(
+        image: &RgbaImage,
+        hex_pixel_size: f32,
+        height: usize,
+        width: usize,
+        mut sample_fn: F,
+    ) -> Self
+    where
+        F: FnMut(Rgba<u8>) -> T,
+    {
+        let (left, _, _, top) = bounds(height, width);
+        let mut tiles = Vec::with_capacity(height * width);
+        for x in 0..height {
+            for y in 0..width {
+                let (cx, cy) = pos(x as isize, y as isize);
+                let i = ((cx - left) * hex_pixel_size).floor().max(0.0) as u32;
+                let j = ((top - cy) * hex_pixel_size).floor().max(0.0) as u32;
+                let i = i.min(image.width().saturating_sub(1));
+                let j = j.min(image.height().saturating_sub(1));
+                let pixel = if image.width() == 0 || image.height() == 0 {
+                    Rgba([0, 0, 0, 0])
+                } else {
+                    *image.get_pixel(i, j)
+                };
+                tiles.push(sample_fn(pixel));
+            }
+        }
+        HexArray {
+            height,
+            width,
+            tiles,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // to_image tests
+
+    #[test]
+    fn test_to_image_size() {
+        let hex_array = HexArray::new(3, 2, 0);
+        let image = hex_array.to_image(10.0, |_| Rgba([255, 0, 0, 255]));
+        assert_eq!(image.width(), 50);
+        assert_eq!(image.height(), 44);
+    }
+
+    #[test]
+    fn test_to_image() {
+        let mut hex_array = HexArray::new(2, 2, 0.0f32);
+        hex_array.set(1, 1, 1.0);
+        let image = hex_array.to_image(10.0, |value| {
+            let v = (value * 255.0) as u8;
+            Rgba([v, v, v, 255])
+        });
+        // center of (0, 0) and (1, 1), with north up
+        assert_eq!(image.get_pixel(10, 34), &Rgba([0, 0, 0, 255]));
+        assert_eq!(image.get_pixel(25, 8), &Rgba([255, 255, 255, 255]));
+        // top left corner is outside of every tile
+        assert_eq!(image.get_pixel(0, 0), &Rgba([0, 0, 0, 0]));
+    }
+
+    // from_image tests
+
+    #[test]
+    fn test_from_image_round_trip() {
+        let mut hex_array = HexArray::new(4, 3, 0u8);
+        for x in 0..4 {
+            for y in 0..3 {
+                hex_array.set(x, y, (x * 3 + y) as u8 * 10);
+            }
+        }
+        let image = hex_array.to_image(8.0, |value| Rgba([*value, 0, 0, 255]));
+        let result = HexArray::from_image(&image, 8.0, 4, 3, |pixel| pixel[0]);
+        for x in 0..4 {
+            for y in 0..3 {
+                assert_eq!(result.get(x, y), hex_array.get(x, y));
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_image_small() {
+        let image = RgbaImage::from_pixel(1, 1, Rgba([1, 2, 3, 4]));
+        let result = HexArray::from_image(&image, 10.0, 2, 2, |pixel| pixel[2]);
+        assert_eq!(result.get(1, 1), Some(&3));
+    }
+}
@@ -1,287 +1,449 @@
-use crate::HexArray;
-
-#[doc(hidden)]
-#[allow(non_upper_case_globals, unused_attributes, unused_qualifications)]
-const _: () = {
-    #[allow(unused_extern_crates, clippy::useless_attribute)]
-    extern crate serde as _serde;
-    #[automatically_derived]
-    impl<T> _serde::Serialize for HexArray<T>
+//! Serde support for HexArray.
+//!
+//! By default a HexArray is serialized as a struct with `height`, `width` and a flat list of
+//! `tiles`, a HexLayers as the sequence of its layers, and a HexPatch as a struct with the
+//! `source` and `target` dimensions and a list of changed `tiles`. The submodules provide
+//! alternative representations for use with `#[serde(with = "...")]`.
+
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+
+use ::serde::de::{self, Deserialize, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use ::serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use crate::{HexArray, HexLayers, HexPatch, Layers};
+
+pub mod packed;
+pub mod rle;
+pub mod rows;
+
+/// The `height`, `width` and `tiles` fields shared by the struct representations.
+struct Fields<V> {
+    height: usize,
+    width: usize,
+    tiles: V,
+}
+
+impl<V> Serialize for Fields<V>
+where
+    V: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("HexArray", 3)?;
+        state.serialize_field("height", &self.height)?;
+        state.serialize_field("width", &self.width)?;
+        state.serialize_field("tiles", &self.tiles)?;
+        state.end()
+    }
+}
+
+enum Field {
+    Height,
+    Width,
+    Tiles,
+    Ignore,
+}
+
+struct FieldVisitor;
+
+impl<'de> Visitor<'de> for FieldVisitor {
+    type Value = Field;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("field identifier")
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Field, E>
     where
-        T: _serde::Serialize,
+        E: de::Error,
     {
-        fn serialize<__S>(
-            &self,
-            __serializer: __S,
-        ) -> _serde::__private::Result<__S::Ok, __S::Error>
-        where
-            __S: _serde::Serializer,
-        {
-            let mut __serde_state = _serde::Serializer::serialize_struct(
-                __serializer,
-                "HexArray",
-                false as usize + 1 + 1 + 1,
-            )?;
-            _serde::ser::SerializeStruct::serialize_field(
-                &mut __serde_state,
-                "height",
-                &self.height,
-            )?;
-            _serde::ser::SerializeStruct::serialize_field(
-                &mut __serde_state,
-                "width",
-                &self.width,
-            )?;
-            _serde::ser::SerializeStruct::serialize_field(
-                &mut __serde_state,
-                "tiles",
-                &self.tiles,
-            )?;
-            _serde::ser::SerializeStruct::end(__serde_state)
+        match value {
+            0 => Ok(Field::Height),
+            1 => Ok(Field::Width),
+            2 => Ok(Field::Tiles),
+            _ => Ok(Field::Ignore),
         }
     }
-};
-
-#[doc(hidden)]
-#[allow(non_upper_case_globals, unused_attributes, unused_qualifications)]
-const _: () = {
-    #[allow(unused_extern_crates, clippy::useless_attribute)]
-    extern crate serde as _serde;
-    #[automatically_derived]
-    impl<'de, T> _serde::Deserialize<'de> for HexArray<T>
+
+    fn visit_str<E>(self, value: &str) -> Result<Field, E>
     where
-        T: _serde::Deserialize<'de>,
+        E: de::Error,
     {
-        fn deserialize<__D>(__deserializer: __D) -> _serde::__private::Result<Self, __D::Error>
-        where
-            __D: _serde::Deserializer<'de>,
-        {
-            #[allow(non_camel_case_types)]
-            #[doc(hidden)]
-            enum __Field {
-                __field0,
-                __field1,
-                __field2,
-                __ignore,
-            }
-            #[doc(hidden)]
-            struct __FieldVisitor;
-            impl<'de> _serde::de::Visitor<'de> for __FieldVisitor {
-                type Value = __Field;
-                fn expecting(
-                    &self,
-                    __formatter: &mut _serde::__private::Formatter,
-                ) -> _serde::__private::fmt::Result {
-                    _serde::__private::Formatter::write_str(__formatter, "field identifier")
-                }
-                fn visit_u64<__E>(self, __value: u64) -> _serde::__private::Result<Self::Value, __E>
-                where
-                    __E: _serde::de::Error,
-                {
-                    match __value {
-                        0u64 => _serde::__private::Ok(__Field::__field0),
-                        1u64 => _serde::__private::Ok(__Field::__field1),
-                        2u64 => _serde::__private::Ok(__Field::__field2),
-                        _ => _serde::__private::Ok(__Field::__ignore),
+        self.visit_bytes(value.as_bytes())
+    }
+
+    fn visit_bytes<E>(self, value: &[u8]) -> Result<Field, E>
+    where
+        E: de::Error,
+    {
+        match value {
+            b"height" => Ok(Field::Height),
+            b"width" => Ok(Field::Width),
+            b"tiles" => Ok(Field::Tiles),
+            _ => Ok(Field::Ignore),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Field {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_identifier(FieldVisitor)
+    }
+}
+
+struct FieldsVisitor<V> {
+    marker: PhantomData<V>,
+}
+
+impl<'de, V> Visitor<'de> for FieldsVisitor<V>
+where
+    V: Deserialize<'de>,
+{
+    type Value = Fields<V>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("struct HexArray")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let height = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &"struct HexArray with 3 elements"))?;
+        let width = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &"struct HexArray with 3 elements"))?;
+        let tiles = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(2, &"struct HexArray with 3 elements"))?;
+        Ok(Fields {
+            height,
+            width,
+            tiles,
+        })
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut height = None;
+        let mut width = None;
+        let mut tiles = None;
+        while let Some(key) = map.next_key()? {
+            match key {
+                Field::Height => {
+                    if height.is_some() {
+                        return Err(de::Error::duplicate_field("height"));
                     }
+                    height = Some(map.next_value()?);
                 }
-                fn visit_str<__E>(
-                    self,
-                    __value: &str,
-                ) -> _serde::__private::Result<Self::Value, __E>
-                where
-                    __E: _serde::de::Error,
-                {
-                    match __value {
-                        "height" => _serde::__private::Ok(__Field::__field0),
-                        "width" => _serde::__private::Ok(__Field::__field1),
-                        "tiles" => _serde::__private::Ok(__Field::__field2),
-                        _ => _serde::__private::Ok(__Field::__ignore),
+                Field::Width => {
+                    if width.is_some() {
+                        return Err(de::Error::duplicate_field("width"));
                     }
+                    width = Some(map.next_value()?);
                 }
-                fn visit_bytes<__E>(
-                    self,
-                    __value: &[u8],
-                ) -> _serde::__private::Result<Self::Value, __E>
-                where
-                    __E: _serde::de::Error,
-                {
-                    match __value {
-                        b"height" => _serde::__private::Ok(__Field::__field0),
-                        b"width" => _serde::__private::Ok(__Field::__field1),
-                        b"tiles" => _serde::__private::Ok(__Field::__field2),
-                        _ => _serde::__private::Ok(__Field::__ignore),
+                Field::Tiles => {
+                    if tiles.is_some() {
+                        return Err(de::Error::duplicate_field("tiles"));
                     }
+                    tiles = Some(map.next_value()?);
                 }
-            }
-            impl<'de> _serde::Deserialize<'de> for __Field {
-                #[inline]
-                fn deserialize<__D>(
-                    __deserializer: __D,
-                ) -> _serde::__private::Result<Self, __D::Error>
-                where
-                    __D: _serde::Deserializer<'de>,
-                {
-                    _serde::Deserializer::deserialize_identifier(__deserializer, __FieldVisitor)
+                Field::Ignore => {
+                    map.next_value::<IgnoredAny>()?;
                 }
             }
-            #[doc(hidden)]
-            struct __Visitor<'de, T>
-            where
-                T: _serde::Deserialize<'de>,
-            {
-                marker: _serde::__private::PhantomData<HexArray<T>>,
-                lifetime: _serde::__private::PhantomData<&'de ()>,
-            }
-            impl<'de, T> _serde::de::Visitor<'de> for __Visitor<'de, T>
-            where
-                T: _serde::Deserialize<'de>,
-            {
-                type Value = HexArray<T>;
-                fn expecting(
-                    &self,
-                    __formatter: &mut _serde::__private::Formatter,
-                ) -> _serde::__private::fmt::Result {
-                    _serde::__private::Formatter::write_str(__formatter, "struct HexArray")
+        }
+        Ok(Fields {
+            height: height.ok_or_else(|| de::Error::missing_field("height"))?,
+            width: width.ok_or_else(|| de::Error::missing_field("width"))?,
+            tiles: tiles.ok_or_else(|| de::Error::missing_field("tiles"))?,
+        })
+    }
+}
+
+impl<'de, V> Deserialize<'de> for Fields<V>
+where
+    V: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &["height", "width", "tiles"];
+        deserializer.deserialize_struct(
+            "HexArray",
+            FIELDS,
+            FieldsVisitor {
+                marker: PhantomData,
+            },
+        )
+    }
+}
+
+/// Check that the number of tiles matches the dimensions.
+fn check_len<E>(height: usize, width: usize, len: usize) -> Result<(), E>
+where
+    E: de::Error,
+{
+    if height.checked_mul(width) == Some(len) {
+        Ok(())
+    } else {
+        Err(E::custom(format_args!(
+            "expected {} tiles for a {}x{} HexArray, found {}",
+            height.saturating_mul(width),
+            height,
+            width,
+            len
+        )))
+    }
+}
+
+impl<T> Serialize for HexArray<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Fields {
+            height: self.height,
+            width: self.width,
+            tiles: &self.tiles,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for HexArray<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let fields = Fields::<Vec<T>>::deserialize(deserializer)?;
+        check_len(fields.height, fields.width, fields.tiles.len())?;
+        Ok(HexArray {
+            height: fields.height,
+            width: fields.width,
+            tiles: fields.tiles,
+        })
+    }
+}
+
+impl<L> Serialize for HexLayers<L>
+where
+    L: Layers + Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.layers().serialize(serializer)
+    }
+}
+
+impl<'de, L> Deserialize<'de> for HexLayers<L>
+where
+    L: Layers + Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        HexLayers::from_layers(L::deserialize(deserializer)?)
+            .ok_or_else(|| de::Error::custom("expected layers with the same dimensions"))
+    }
+}
+
+impl<T> Serialize for HexPatch<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("HexPatch", 3)?;
+        state.serialize_field("source", &self.source())?;
+        state.serialize_field("target", &self.target())?;
+        state.serialize_field("tiles", self.tiles())?;
+        state.end()
+    }
+}
+
+enum PatchField {
+    Source,
+    Target,
+    Tiles,
+    Ignore,
+}
+
+struct PatchFieldVisitor;
+
+impl<'de> Visitor<'de> for PatchFieldVisitor {
+    type Value = PatchField;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("field identifier")
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<PatchField, E>
+    where
+        E: de::Error,
+    {
+        match value {
+            0 => Ok(PatchField::Source),
+            1 => Ok(PatchField::Target),
+            2 => Ok(PatchField::Tiles),
+            _ => Ok(PatchField::Ignore),
+        }
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<PatchField, E>
+    where
+        E: de::Error,
+    {
+        self.visit_bytes(value.as_bytes())
+    }
+
+    fn visit_bytes<E>(self, value: &[u8]) -> Result<PatchField, E>
+    where
+        E: de::Error,
+    {
+        match value {
+            b"source" => Ok(PatchField::Source),
+            b"target" => Ok(PatchField::Target),
+            b"tiles" => Ok(PatchField::Tiles),
+            _ => Ok(PatchField::Ignore),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for PatchField {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_identifier(PatchFieldVisitor)
+    }
+}
+
+struct PatchVisitor<T> {
+    marker: PhantomData<T>,
+}
+
+impl<'de, T> Visitor<'de> for PatchVisitor<T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = HexPatch<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("struct HexPatch")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let source = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &"struct HexPatch with 3 elements"))?;
+        let target = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &"struct HexPatch with 3 elements"))?;
+        let tiles = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(2, &"struct HexPatch with 3 elements"))?;
+        patch(source, target, tiles)
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut source = None;
+        let mut target = None;
+        let mut tiles = None;
+        while let Some(key) = map.next_key()? {
+            match key {
+                PatchField::Source => {
+                    if source.is_some() {
+                        return Err(de::Error::duplicate_field("source"));
+                    }
+                    source = Some(map.next_value()?);
                 }
-                #[inline]
-                fn visit_seq<__A>(
-                    self,
-                    mut __seq: __A,
-                ) -> _serde::__private::Result<Self::Value, __A::Error>
-                where
-                    __A: _serde::de::SeqAccess<'de>,
-                {
-                    let __field0 = match _serde::de::SeqAccess::next_element::<usize>(&mut __seq)? {
-                        _serde::__private::Some(__value) => __value,
-                        _serde::__private::None => {
-                            return _serde::__private::Err(_serde::de::Error::invalid_length(
-                                0usize,
-                                &"struct HexArray with 3 elements",
-                            ));
-                        }
-                    };
-                    let __field1 = match _serde::de::SeqAccess::next_element::<usize>(&mut __seq)? {
-                        _serde::__private::Some(__value) => __value,
-                        _serde::__private::None => {
-                            return _serde::__private::Err(_serde::de::Error::invalid_length(
-                                1usize,
-                                &"struct HexArray with 3 elements",
-                            ));
-                        }
-                    };
-                    let __field2 = match _serde::de::SeqAccess::next_element::<Vec<T>>(&mut __seq)?
-                    {
-                        _serde::__private::Some(__value) => __value,
-                        _serde::__private::None => {
-                            return _serde::__private::Err(_serde::de::Error::invalid_length(
-                                2usize,
-                                &"struct HexArray with 3 elements",
-                            ));
-                        }
-                    };
-                    _serde::__private::Ok(HexArray {
-                        height: __field0,
-                        width: __field1,
-                        tiles: __field2,
-                    })
+                PatchField::Target => {
+                    if target.is_some() {
+                        return Err(de::Error::duplicate_field("target"));
+                    }
+                    target = Some(map.next_value()?);
                 }
-                #[inline]
-                fn visit_map<__A>(
-                    self,
-                    mut __map: __A,
-                ) -> _serde::__private::Result<Self::Value, __A::Error>
-                where
-                    __A: _serde::de::MapAccess<'de>,
-                {
-                    let mut __field0: _serde::__private::Option<usize> = _serde::__private::None;
-                    let mut __field1: _serde::__private::Option<usize> = _serde::__private::None;
-                    let mut __field2: _serde::__private::Option<Vec<T>> = _serde::__private::None;
-                    while let _serde::__private::Some(__key) =
-                        _serde::de::MapAccess::next_key::<__Field>(&mut __map)?
-                    {
-                        match __key {
-                            __Field::__field0 => {
-                                if _serde::__private::Option::is_some(&__field0) {
-                                    return _serde::__private::Err(
-                                        <__A::Error as _serde::de::Error>::duplicate_field(
-                                            "height",
-                                        ),
-                                    );
-                                }
-                                __field0 =
-                                    _serde::__private::Some(_serde::de::MapAccess::next_value::<
-                                        usize,
-                                    >(
-                                        &mut __map
-                                    )?);
-                            }
-                            __Field::__field1 => {
-                                if _serde::__private::Option::is_some(&__field1) {
-                                    return _serde::__private::Err(
-                                        <__A::Error as _serde::de::Error>::duplicate_field("width"),
-                                    );
-                                }
-                                __field1 =
-                                    _serde::__private::Some(_serde::de::MapAccess::next_value::<
-                                        usize,
-                                    >(
-                                        &mut __map
-                                    )?);
-                            }
-                            __Field::__field2 => {
-                                if _serde::__private::Option::is_some(&__field2) {
-                                    return _serde::__private::Err(
-                                        <__A::Error as _serde::de::Error>::duplicate_field("tiles"),
-                                    );
-                                }
-                                __field2 =
-                                    _serde::__private::Some(_serde::de::MapAccess::next_value::<
-                                        Vec<T>,
-                                    >(
-                                        &mut __map
-                                    )?);
-                            }
-                            _ => {
-                                let _ = _serde::de::MapAccess::next_value::<_serde::de::IgnoredAny>(
-                                    &mut __map,
-                                )?;
-                            }
-                        }
+                PatchField::Tiles => {
+                    if tiles.is_some() {
+                        return Err(de::Error::duplicate_field("tiles"));
                     }
-                    let __field0 = match __field0 {
-                        _serde::__private::Some(__field0) => __field0,
-                        _serde::__private::None => _serde::__private::de::missing_field("height")?,
-                    };
-                    let __field1 = match __field1 {
-                        _serde::__private::Some(__field1) => __field1,
-                        _serde::__private::None => _serde::__private::de::missing_field("width")?,
-                    };
-                    let __field2 = match __field2 {
-                        _serde::__private::Some(__field2) => __field2,
-                        _serde::__private::None => _serde::__private::de::missing_field("tiles")?,
-                    };
-                    _serde::__private::Ok(HexArray {
-                        height: __field0,
-                        width: __field1,
-                        tiles: __field2,
-                    })
+                    tiles = Some(map.next_value()?);
+                }
+                PatchField::Ignore => {
+                    map.next_value::<IgnoredAny>()?;
                 }
             }
-            #[doc(hidden)]
-            const FIELDS: &'static [&'static str] = &["height", "width", "tiles"];
-            _serde::Deserializer::deserialize_struct(
-                __deserializer,
-                "HexArray",
-                FIELDS,
-                __Visitor {
-                    marker: _serde::__private::PhantomData::<HexArray<T>>,
-                    lifetime: _serde::__private::PhantomData,
-                },
-            )
         }
+        patch(
+            source.ok_or_else(|| de::Error::missing_field("source"))?,
+            target.ok_or_else(|| de::Error::missing_field("target"))?,
+            tiles.ok_or_else(|| de::Error::missing_field("tiles"))?,
+        )
     }
-};
+}
+
+/// Create a HexPatch from deserialized fields, checking that they are consistent.
+fn patch<T, E>(
+    source: (usize, usize),
+    target: (usize, usize),
+    tiles: Vec<((usize, usize), T)>,
+) -> Result<HexPatch<T>, E>
+where
+    E: de::Error,
+{
+    HexPatch::new(source, target, tiles).ok_or_else(|| {
+        E::custom("expected sorted tiles inside the target covering every added tile")
+    })
+}
+
+impl<'de, T> Deserialize<'de> for HexPatch<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &["source", "target", "tiles"];
+        deserializer.deserialize_struct(
+            "HexPatch",
+            FIELDS,
+            PatchVisitor {
+                marker: PhantomData,
+            },
+        )
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -309,4 +471,56 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_deserialize_seq() {
+        let serialized = "[2,1,[3,4]]";
+        let hex_array: HexArray<i32> = serde_json::from_str(serialized).unwrap();
+        assert_eq!(hex_array.get(0, 0), Some(&3));
+        assert_eq!(hex_array.get(1, 0), Some(&4));
+    }
+
+    #[test]
+    fn test_deserialize_wrong_len() {
+        let serialized = "{\"height\":2,\"width\":2,\"tiles\":[0,0,0]}";
+        assert!(serde_json::from_str::<HexArray<i32>>(serialized).is_err());
+    }
+
+    #[test]
+    fn test_hex_layers() {
+        let mut layers = HexLayers::<(HexArray<u8>, HexArray<bool>)>::new(1, 2, (0, false));
+        layers.set(0, 1, (3, true));
+        let serialized = serde_json::to_string(&layers).unwrap();
+        assert_eq!(
+            serialized,
+            "[{\"height\":1,\"width\":2,\"tiles\":[0,3]},{\"height\":1,\"width\":2,\"tiles\":[false,true]}]"
+        );
+        let deserialized: HexLayers<(HexArray<u8>, HexArray<bool>)> =
+            serde_json::from_str(&serialized).unwrap();
+        assert!(deserialized == layers);
+        let mismatched = "[{\"height\":1,\"width\":2,\"tiles\":[0,3]},[1,1,[true]]]";
+        assert!(
+            serde_json::from_str::<HexLayers<(HexArray<u8>, HexArray<bool>)>>(mismatched).is_err()
+        );
+    }
+
+    #[test]
+    fn test_hex_patch() {
+        let before = HexArray::new(1, 2, 0u8);
+        let mut after = HexArray::new(2, 1, 0u8);
+        after.set(0, 0, 7);
+        let patch = before.diff(&after);
+        let serialized = serde_json::to_string(&patch).unwrap();
+        assert_eq!(
+            serialized,
+            "{\"source\":[1,2],\"target\":[2,1],\"tiles\":[[[0,0],7],[[1,0],0]]}"
+        );
+        let deserialized: HexPatch<u8> = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, patch);
+        let missing = "{\"source\":[1,2],\"target\":[2,1],\"tiles\":[[[0,0],7]]}";
+        assert!(serde_json::from_str::<HexPatch<u8>>(missing).is_err());
+        let huge =
+            r#"{"source":[8589934592,8589934592],"target":[8589934592,8589934592],"tiles":[]}"#;
+        assert!(serde_json::from_str::<HexPatch<u8>>(huge).is_err());
+    }
 }
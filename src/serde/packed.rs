@@ -0,0 +1,247 @@
+//! Serialize a HexArray with its tiles packed into little-endian bytes.
+//!
+//! Human-readable formats store the bytes as a base64 string, binary formats store them as a
+//! byte array.
+//!
+//! ```json
+//! {"height": 2, "width": 2, "tiles": "AAAAAQAAAAI="}
+//! ```
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use ::serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+use ::serde::ser::{Serialize, Serializer};
+
+use super::{check_len, Fields};
+use crate::base64::{decode, encode};
+use crate::HexArray;
+
+/// A tile with a fixed-size byte representation.
+pub trait Pack: Sized {
+    /// The number of bytes of a packed tile, which must not be zero.
+    const SIZE: usize;
+
+    /// Append the bytes of the tile.
+    fn pack(&self, bytes: &mut Vec<u8>);
+
+    /// Read a tile from exactly `SIZE` bytes.
+    fn unpack(bytes: &[u8]) -> Self;
+}
+
+macro_rules! impl_pack {
+    ($($ty:ty),*) => {
+        $(
+            impl Pack for $ty {
+                const SIZE: usize = core::mem::size_of::<$ty>();
+
+                fn pack(&self, bytes: &mut Vec<u8>) {
+                    bytes.extend_from_slice(&self.to_le_bytes());
+                }
+
+                fn unpack(bytes: &[u8]) -> Self {
+                    <$ty>::from_le_bytes(bytes.try_into().unwrap())
+                }
+            }
+        )*
+    };
+}
+
+impl_pack!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
+
+impl Pack for bool {
+    const SIZE: usize = 1;
+
+    fn pack(&self, bytes: &mut Vec<u8>) {
+        bytes.push(u8::from(*self));
+    }
+
+    fn unpack(bytes: &[u8]) -> Self {
+        bytes[0] != 0
+    }
+}
+
+struct Bytes(Vec<u8>);
+
+impl Serialize for Bytes {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&encode(&self.0))
+        } else {
+            serializer.serialize_bytes(&self.0)
+        }
+    }
+}
+
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Bytes;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("base64 string or bytes")
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Bytes, E>
+    where
+        E: de::Error,
+    {
+        decode(value)
+            .map(Bytes)
+            .ok_or_else(|| E::invalid_value(de::Unexpected::Str(value), &self))
+    }
+
+    fn visit_bytes<E>(self, value: &[u8]) -> Result<Bytes, E>
+    where
+        E: de::Error,
+    {
+        Ok(Bytes(value.to_vec()))
+    }
+
+    fn visit_byte_buf<E>(self, value: Vec<u8>) -> Result<Bytes, E>
+    where
+        E: de::Error,
+    {
+        Ok(Bytes(value))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Bytes, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(Bytes(bytes))
+    }
+}
+
+impl<'de> Deserialize<'de> for Bytes {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(BytesVisitor)
+        } else {
+            deserializer.deserialize_byte_buf(BytesVisitor)
+        }
+    }
+}
+
+/// Serialize the HexArray with packed tiles.
+pub fn serialize<T, S>(hex_array: &HexArray<T>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Pack,
+    S: Serializer,
+{
+    let mut bytes = Vec::with_capacity(hex_array.tiles.len() * T::SIZE);
+    for tile in &hex_array.tiles {
+        tile.pack(&mut bytes);
+    }
+    Fields {
+        height: hex_array.height,
+        width: hex_array.width,
+        tiles: Bytes(bytes),
+    }
+    .serialize(serializer)
+}
+
+/// Deserialize a HexArray with packed tiles.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<HexArray<T>, D::Error>
+where
+    T: Pack,
+    D: Deserializer<'de>,
+{
+    if T::SIZE == 0 {
+        return Err(de::Error::custom("the tile size must not be zero"));
+    }
+    let fields = Fields::<Bytes>::deserialize(deserializer)?;
+    let bytes = fields.tiles.0;
+    if bytes.len() % T::SIZE != 0 {
+        return Err(de::Error::invalid_length(
+            bytes.len(),
+            &"a multiple of the tile size",
+        ));
+    }
+    check_len(fields.height, fields.width, bytes.len() / T::SIZE)?;
+    Ok(HexArray {
+        height: fields.height,
+        width: fields.width,
+        tiles: bytes.chunks_exact(T::SIZE).map(T::unpack).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // serialize tests
+
+    #[test]
+    fn test_serialize() {
+        let mut hex_array = HexArray::new(2, 2, 0u16);
+        hex_array.set(0, 1, 256);
+        hex_array.set(1, 1, 512);
+        let mut serialized = Vec::new();
+        serialize(
+            &hex_array,
+            &mut serde_json::Serializer::new(&mut serialized),
+        )
+        .unwrap();
+        assert_eq!(
+            serialized,
+            b"{\"height\":2,\"width\":2,\"tiles\":\"AAAAAQAAAAI=\"}"
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut hex_array = HexArray::new(3, 5, 0.5f32);
+        hex_array.set(2, 4, -3.25);
+        let mut serialized = Vec::new();
+        serialize(
+            &hex_array,
+            &mut serde_json::Serializer::new(&mut serialized),
+        )
+        .unwrap();
+        let mut deserializer = serde_json::Deserializer::from_slice(&serialized);
+        let result: HexArray<f32> = deserialize(&mut deserializer).unwrap();
+        assert_eq!(result.height(), 3);
+        assert_eq!(result.width(), 5);
+        assert_eq!(result.get(2, 4), Some(&-3.25));
+        assert_eq!(result.get(0, 0), Some(&0.5));
+    }
+
+    #[test]
+    fn test_deserialize_wrong_len() {
+        let mut deserializer =
+            serde_json::Deserializer::from_str("{\"height\":2,\"width\":2,\"tiles\":\"AAE=\"}");
+        let result: Result<HexArray<u16>, _> = deserialize(&mut deserializer);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_deserialize_zero_size() {
+        struct Empty;
+
+        impl Pack for Empty {
+            const SIZE: usize = 0;
+
+            fn pack(&self, _bytes: &mut Vec<u8>) {}
+
+            fn unpack(_bytes: &[u8]) -> Self {
+                Empty
+            }
+        }
+
+        let mut deserializer =
+            serde_json::Deserializer::from_str("{\"height\":2,\"width\":2,\"tiles\":\"\"}");
+        let result: Result<HexArray<Empty>, _> = deserialize(&mut deserializer);
+        assert!(result.is_err());
+    }
+}
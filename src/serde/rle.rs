@@ -0,0 +1,100 @@
+//! Serialize a HexArray with its tiles run-length encoded, in index order.
+//!
+//! ```json
+//! {"height": 2, "width": 3, "tiles": [[5, 0], [1, 7]]}
+//! ```
+//!
+//! Every run is a pair of a count and the value repeated that many times.
+
+use alloc::vec::Vec;
+
+use ::serde::de::{self, Deserialize, Deserializer};
+use ::serde::ser::{Serialize, Serializer};
+
+use super::{check_len, Fields};
+use crate::HexArray;
+
+/// Serialize the HexArray with run-length encoded tiles.
+pub fn serialize<T, S>(hex_array: &HexArray<T>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Serialize + PartialEq,
+    S: Serializer,
+{
+    let mut runs: Vec<(usize, &T)> = Vec::new();
+    for tile in &hex_array.tiles {
+        match runs.last_mut() {
+            Some((count, value)) if *value == tile => *count += 1,
+            _ => runs.push((1, tile)),
+        }
+    }
+    Fields {
+        height: hex_array.height,
+        width: hex_array.width,
+        tiles: runs,
+    }
+    .serialize(serializer)
+}
+
+/// Deserialize a HexArray with run-length encoded tiles.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<HexArray<T>, D::Error>
+where
+    T: Deserialize<'de> + Clone,
+    D: Deserializer<'de>,
+{
+    let fields = Fields::<Vec<(usize, T)>>::deserialize(deserializer)?;
+    let len = fields
+        .tiles
+        .iter()
+        .try_fold(0usize, |len, (count, _)| len.checked_add(*count))
+        .ok_or_else(|| de::Error::custom("run lengths overflow"))?;
+    check_len(fields.height, fields.width, len)?;
+    let mut tiles = Vec::with_capacity(len);
+    for (count, value) in fields.tiles {
+        tiles.extend(core::iter::repeat_n(value, count));
+    }
+    Ok(HexArray {
+        height: fields.height,
+        width: fields.width,
+        tiles,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize() {
+        let mut hex_array = HexArray::new(2, 3, 0);
+        hex_array.set(1, 2, 7);
+        let mut serialized = Vec::new();
+        serialize(
+            &hex_array,
+            &mut serde_json::Serializer::new(&mut serialized),
+        )
+        .unwrap();
+        assert_eq!(
+            serialized,
+            b"{\"height\":2,\"width\":3,\"tiles\":[[5,0],[1,7]]}"
+        );
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let mut deserializer = serde_json::Deserializer::from_str(
+            "{\"height\":2,\"width\":3,\"tiles\":[[2,1],[3,0],[1,7]]}",
+        );
+        let hex_array: HexArray<i32> = deserialize(&mut deserializer).unwrap();
+        assert_eq!(hex_array.get(0, 1), Some(&1));
+        assert_eq!(hex_array.get(0, 2), Some(&0));
+        assert_eq!(hex_array.get(1, 2), Some(&7));
+    }
+
+    #[test]
+    fn test_deserialize_wrong_len() {
+        let mut deserializer =
+            serde_json::Deserializer::from_str("{\"height\":2,\"width\":3,\"tiles\":[[2,1]]}");
+        let result: Result<HexArray<i32>, _> = deserialize(&mut deserializer);
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,90 @@
+//! Serialize a HexArray as a list of rows, one per x index.
+//!
+//! ```json
+//! [[0, 1, 2], [3, 4, 5]]
+//! ```
+//!
+//! The width of a HexArray without rows is not preserved.
+
+use alloc::vec::Vec;
+
+use ::serde::de::{self, Deserialize, Deserializer};
+use ::serde::ser::{Serialize, SerializeSeq, Serializer};
+
+use crate::HexArray;
+
+/// Serialize the HexArray as a list of rows.
+pub fn serialize<T, S>(hex_array: &HexArray<T>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Serialize,
+    S: Serializer,
+{
+    let mut seq = serializer.serialize_seq(Some(hex_array.height))?;
+    for x in 0..hex_array.height {
+        seq.serialize_element(&hex_array.tiles[x * hex_array.width..(x + 1) * hex_array.width])?;
+    }
+    seq.end()
+}
+
+/// Deserialize a HexArray from a list of rows of equal length.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<HexArray<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    let rows = Vec::<Vec<T>>::deserialize(deserializer)?;
+    let height = rows.len();
+    let width = rows.first().map_or(0, Vec::len);
+    let mut tiles = Vec::with_capacity(height * width);
+    for (x, row) in rows.into_iter().enumerate() {
+        if row.len() != width {
+            return Err(de::Error::custom(format_args!(
+                "expected row {} to have {} tiles, found {}",
+                x,
+                width,
+                row.len()
+            )));
+        }
+        tiles.extend(row);
+    }
+    Ok(HexArray {
+        height,
+        width,
+        tiles,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize() {
+        let mut hex_array = HexArray::new(2, 3, 0);
+        hex_array.set(1, 2, 5);
+        let mut serialized = Vec::new();
+        serialize(
+            &hex_array,
+            &mut serde_json::Serializer::new(&mut serialized),
+        )
+        .unwrap();
+        assert_eq!(serialized, b"[[0,0,0],[0,0,5]]");
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let mut deserializer = serde_json::Deserializer::from_str("[[1,2,3],[4,5,6]]");
+        let hex_array: HexArray<i32> = deserialize(&mut deserializer).unwrap();
+        assert_eq!(hex_array.height(), 2);
+        assert_eq!(hex_array.width(), 3);
+        assert_eq!(hex_array.get(0, 2), Some(&3));
+        assert_eq!(hex_array.get(1, 0), Some(&4));
+    }
+
+    #[test]
+    fn test_deserialize_ragged() {
+        let mut deserializer = serde_json::Deserializer::from_str("[[1,2,3],[4,5]]");
+        let result: Result<HexArray<i32>, _> = deserialize(&mut deserializer);
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,250 @@
+//! Render a HexArray as an SVG image.
+//!
+//! Tiles are drawn with the geometry of `HexArray::corners`, flipped vertically so that north
+//! points up in the image.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::{bounds, pos, HexArray};
+
+/// The style of a single tile.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TileStyle {
+    /// The fill color of the tile.
+    pub fill: String,
+    /// The outline color of the tile.
+    pub stroke: String,
+    /// The text drawn at the center of the tile.
+    pub label: Option<String>,
+}
+
+impl Default for TileStyle {
+    fn default() -> Self {
+        TileStyle {
+            fill: "white".to_string(),
+            stroke: "black".to_string(),
+            label: None,
+        }
+    }
+}
+
+/// A shape drawn on top of the tiles.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Overlay {
+    /// A line through the centers of the given tiles, in order.
+    Path {
+        tiles: Vec<(usize, usize)>,
+        stroke: String,
+    },
+    /// The given tiles drawn again with the given colors.
+    Highlight {
+        tiles: Vec<(usize, usize)>,
+        fill: String,
+        stroke: String,
+    },
+}
+
+/// Escape text for use in XML content and attributes.
+fn escape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&apos;"),
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+struct Canvas {
+    size: f32,
+    top: f32,
+    left: f32,
+    svg: String,
+}
+
+impl Canvas {
+    fn point(&self, (x, y): (f32, f32)) -> (f32, f32) {
+        ((x - self.left) * self.size, (self.top - y) * self.size)
+    }
+
+    fn polygon(&mut self, x: usize, y: usize, fill: &str, stroke: &str) {
+        let corners = HexArray::<()>::corner_pos(x, y);
+        let points: Vec<String> = corners
+            .iter()
+            .map(|corner| {
+                let (x, y) = self.point(*corner);
+                format!("{:.2},{:.2}", x, y)
+            })
+            .collect();
+        let _ = writeln!(
+            self.svg,
+            "<polygon points=\"{}\" fill=\"{}\" stroke=\"{}\"/>",
+            points.join(" "),
+            escape(fill),
+            escape(stroke)
+        );
+    }
+}
+
+/// Render the HexArray as an SVG document, styling every tile with the given function.
+///
+/// `size` is the distance in pixels from the center of a tile to its corners.
+pub fn render<T, F>(
+    hex_array: &HexArray<T>,
+    size: f32,
+    overlays: &[Overlay],
+    mut style: F,
+) -> String
+where
+    F: FnMut(&T) -> TileStyle,
+{
+    let (left, right, bottom, top) = bounds(hex_array.height, hex_array.width);
+    let mut canvas = Canvas {
+        size,
+        top,
+        left,
+        svg: String::new(),
+    };
+    let _ = writeln!(
+        canvas.svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{:.2}\" height=\"{:.2}\">",
+        (right - left) * size,
+        (top - bottom) * size
+    );
+    let mut labels = Vec::new();
+    for x in 0..hex_array.height {
+        for y in 0..hex_array.width {
+            let tile_style = style(&hex_array.tiles[x * hex_array.width + y]);
+            canvas.polygon(x, y, &tile_style.fill, &tile_style.stroke);
+            if let Some(label) = tile_style.label {
+                labels.push((x, y, label));
+            }
+        }
+    }
+    for overlay in overlays {
+        match overlay {
+            Overlay::Path { tiles, stroke } => {
+                let points: Vec<String> = tiles
+                    .iter()
+                    .filter(|(x, y)| hex_array.get(*x, *y).is_some())
+                    .map(|(x, y)| {
+                        let (x, y) = canvas.point(pos(*x as isize, *y as isize));
+                        format!("{:.2},{:.2}", x, y)
+                    })
+                    .collect();
+                let _ = writeln!(
+                    canvas.svg,
+                    "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\"/>",
+                    points.join(" "),
+                    escape(stroke)
+                );
+            }
+            Overlay::Highlight {
+                tiles,
+                fill,
+                stroke,
+            } => {
+                for (x, y) in tiles {
+                    if hex_array.get(*x, *y).is_some() {
+                        canvas.polygon(*x, *y, fill, stroke);
+                    }
+                }
+            }
+        }
+    }
+    for (x, y, label) in labels {
+        let (x, y) = canvas.point(pos(x as isize, y as isize));
+        let _ = writeln!(
+            canvas.svg,
+            "<text x=\"{:.2}\" y=\"{:.2}\" font-size=\"{:.2}\" text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>",
+            x,
+            y,
+            size / 2.0,
+            escape(&label)
+        );
+    }
+    canvas.svg.push_str("</svg>\n");
+    canvas.svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // escape tests
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("a<b>&\"c'"), "a&lt;b&gt;&amp;&quot;c&apos;");
+    }
+
+    // render tests
+
+    #[test]
+    fn test_render_single() {
+        let hex_array = HexArray::new(1, 1, 0);
+        let svg = render(&hex_array, 10.0, &[], |_| TileStyle::default());
+        assert_eq!(
+            svg,
+            concat!(
+                "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"20.00\" height=\"17.32\">\n",
+                "<polygon points=\"15.00,0.00 20.00,8.66 15.00,17.32 5.00,17.32 0.00,8.66 5.00,0.00\" fill=\"white\" stroke=\"black\"/>\n",
+                "</svg>\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_render_size() {
+        let hex_array = HexArray::new(3, 2, 0);
+        let svg = render(&hex_array, 1.0, &[], |_| TileStyle::default());
+        assert!(svg.starts_with(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"5.00\" height=\"4.33\">"
+        ));
+        assert_eq!(svg.matches("<polygon").count(), 6);
+    }
+
+    #[test]
+    fn test_render_labels() {
+        let mut hex_array = HexArray::new(2, 2, 0);
+        hex_array.set(1, 1, 7);
+        let svg = render(&hex_array, 10.0, &[], |tile| TileStyle {
+            fill: if *tile == 7 { "red" } else { "blue" }.to_string(),
+            stroke: "black".to_string(),
+            label: (*tile == 7).then(|| "<7>".to_string()),
+        });
+        assert_eq!(svg.matches("fill=\"red\"").count(), 1);
+        assert_eq!(svg.matches("fill=\"blue\"").count(), 3);
+        assert!(svg.contains(">&lt;7&gt;</text>"));
+    }
+
+    #[test]
+    fn test_render_overlays() {
+        let hex_array = HexArray::new(2, 2, 0);
+        let overlays = [
+            Overlay::Path {
+                tiles: vec![(0, 0), (1, 0), (5, 5)],
+                stroke: "green".to_string(),
+            },
+            Overlay::Highlight {
+                tiles: vec![(1, 1), (9, 9)],
+                fill: "yellow".to_string(),
+                stroke: "orange".to_string(),
+            },
+        ];
+        let svg = render(&hex_array, 10.0, &overlays, |_| TileStyle::default());
+        assert!(svg.contains(
+            "<polyline points=\"10.00,34.64 25.00,25.98\" fill=\"none\" stroke=\"green\"/>"
+        ));
+        assert_eq!(svg.matches("<polygon").count(), 5);
+        assert_eq!(svg.matches("fill=\"yellow\"").count(), 1);
+    }
+}
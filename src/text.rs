@@ -0,0 +1,341 @@
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::error::Error;
+use core::fmt;
+
+use crate::HexArray;
+
+/// The characters used to draw the outlines of the tiles.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TextStyle {
+    /// Draw outlines with `/`, `\` and `_`.
+    #[default]
+    Ascii,
+    /// Draw outlines with `╱`, `╲` and `_`.
+    Unicode,
+}
+
+impl TextStyle {
+    fn chars(self) -> (char, char) {
+        match self {
+            TextStyle::Ascii => ('/', '\\'),
+            TextStyle::Unicode => ('╱', '╲'),
+        }
+    }
+}
+
+/// An error returned when parsing a HexArray from text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseTextError<E> {
+    /// The text is not a grid of tile outlines.
+    Shape,
+    /// The tile at the given indices could not be parsed.
+    Tile { x: usize, y: usize, error: E },
+}
+
+impl<E> fmt::Display for ParseTextError<E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseTextError::Shape => write!(f, "text is not a grid of hexagonal tiles"),
+            ParseTextError::Tile { x, y, error } => {
+                write!(f, "invalid tile at ({}, {}): {}", x, y, error)
+            }
+        }
+    }
+}
+
+impl<E> Error for ParseTextError<E> where E: fmt::Debug + fmt::Display {}
+
+/// Get the column of the left corner and the line of the top edge of a tile.
+fn origin(x: usize, y: usize, width: usize, cell: usize) -> (usize, usize) {
+    (
+        x * (cell + 3),
+        2 * (width - 1 - y) + usize::from(x.is_multiple_of(2)),
+    )
+}
+
+impl<T> HexArray<T> {
+    /// Draw the HexArray as text, labelling every tile with the given function.
+    ///
+    /// Tiles are drawn as flat-topped hexagons with north up, odd columns raised by half a tile
+    /// to match `position`.
+    pub fn to_text<F>(&self, style: TextStyle, mut f: F) -> String
+    where
+        F: FnMut(&T) -> String,
+    {
+        if self.height == 0 || self.width == 0 {
+            return String::new();
+        }
+        let labels: Vec<Vec<char>> = self
+            .tiles
+            .iter()
+            .map(|tile| f(tile).chars().collect())
+            .collect();
+        let cell = labels.iter().map(Vec::len).max().unwrap_or(0).max(1);
+        let (left, right) = style.chars();
+        let mut canvas = vec![vec![' '; self.height * (cell + 3) + 1]; 2 * self.width + 2];
+        for x in 0..self.height {
+            for y in 0..self.width {
+                let (column, line) = origin(x, y, self.width, cell);
+                for i in 1..cell + 3 {
+                    canvas[line][column + i] = '_';
+                    canvas[line + 2][column + i] = '_';
+                }
+                canvas[line + 1][column] = left;
+                canvas[line + 1][column + cell + 3] = right;
+                canvas[line + 2][column] = right;
+                canvas[line + 2][column + cell + 3] = left;
+                let label = &labels[x * self.width + y];
+                let start = column + 2 + (cell - label.len()) / 2;
+                canvas[line + 1][start..start + label.len()].copy_from_slice(label);
+            }
+        }
+        if self.height == 1 {
+            canvas.remove(0);
+        }
+        let mut result = String::new();
+        for line in canvas {
+            let line: String = line.into_iter().collect();
+            result.push_str(line.trim_end());
+            result.push('\n');
+        }
+        result
+    }
+
+    /// Parse a HexArray from text drawn by `to_text`, parsing every label with the given function.
+    ///
+    /// Blank lines around the drawing and common indentation are ignored.
+    pub fn from_text<F, E>(text: &str, mut f: F) -> Result<Self, ParseTextError<E>>
+    where
+        F: FnMut(&str) -> Result<T, E>,
+    {
+        let mut lines: Vec<Vec<char>> = text
+            .lines()
+            .map(|line| line.trim_end().chars().collect())
+            .collect();
+        while lines.last().is_some_and(Vec::is_empty) {
+            lines.pop();
+        }
+        let first = lines.iter().position(|line| !line.is_empty());
+        let first = match first {
+            Some(first) => first,
+            None => {
+                return Ok(HexArray {
+                    height: 0,
+                    width: 0,
+                    tiles: Vec::new(),
+                })
+            }
+        };
+        lines.drain(..first);
+        let indent = lines
+            .iter()
+            .filter(|line| !line.is_empty())
+            .map(|line| line.iter().take_while(|c| **c == ' ').count())
+            .min()
+            .unwrap_or(0);
+        for line in lines.iter_mut() {
+            line.drain(..indent.min(line.len()));
+        }
+
+        let start = lines[0]
+            .iter()
+            .position(|c| *c == '_')
+            .ok_or(ParseTextError::Shape)?;
+        let run = lines[0][start..].iter().take_while(|c| **c == '_').count();
+        if run < 3 {
+            return Err(ParseTextError::Shape);
+        }
+        let cell = run - 2;
+        if start == 1 {
+            lines.insert(0, Vec::new());
+        }
+        if lines.len() < 4 || !lines.len().is_multiple_of(2) {
+            return Err(ParseTextError::Shape);
+        }
+        let width = (lines.len() - 2) / 2;
+        let columns = lines.iter().map(Vec::len).max().unwrap_or(0);
+        if columns % (cell + 3) != 1 {
+            return Err(ParseTextError::Shape);
+        }
+        let height = columns / (cell + 3);
+        if (height > 1) != (start != 1) {
+            return Err(ParseTextError::Shape);
+        }
+
+        let mut tiles = Vec::with_capacity(height * width);
+        for x in 0..height {
+            for y in 0..width {
+                let (column, line) = origin(x, y, width, cell);
+                let line = &lines[line + 1];
+                let label: String = line.iter().skip(column + 2).take(cell).collect();
+                let tile = f(label.trim()).map_err(|error| ParseTextError::Tile { x, y, error })?;
+                tiles.push(tile);
+            }
+        }
+        Ok(HexArray {
+            height,
+            width,
+            tiles,
+        })
+    }
+}
+
+impl<T> fmt::Display for HexArray<T>
+where
+    T: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_text(TextStyle::Ascii, |tile| tile.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+
+    // to_text tests
+
+    #[test]
+    fn test_to_text() {
+        let mut hex_array = HexArray::new(3, 2, 0);
+        hex_array.set(1, 1, 1);
+        hex_array.set(2, 0, 2);
+        let text = hex_array.to_text(TextStyle::Ascii, |tile| tile.to_string());
+        assert_eq!(
+            text,
+            concat!(
+                "     ___\n",
+                " ___/ 1 \\___\n",
+                "/ 0 \\___/ 0 \\\n",
+                "\\___/ 0 \\___/\n",
+                "/ 0 \\___/ 2 \\\n",
+                "\\___/   \\___/\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_to_text_single_column() {
+        let hex_array = HexArray::new(1, 2, 7);
+        let text = hex_array.to_text(TextStyle::Ascii, |tile| tile.to_string());
+        assert_eq!(text, " ___\n/ 7 \\\n\\___/\n/ 7 \\\n\\___/\n");
+    }
+
+    #[test]
+    fn test_to_text_unicode() {
+        let hex_array = HexArray::new(1, 1, "λ");
+        let text = hex_array.to_text(TextStyle::Unicode, |tile| tile.to_string());
+        assert_eq!(text, " ___\n╱ λ ╲\n╲___╱\n");
+    }
+
+    #[test]
+    fn test_to_text_wide_labels() {
+        let mut hex_array = HexArray::new(1, 1, 0);
+        hex_array.set(0, 0, 100);
+        let text = hex_array.to_text(TextStyle::Ascii, |tile| tile.to_string());
+        assert_eq!(text, " _____\n/ 100 \\\n\\_____/\n");
+    }
+
+    #[test]
+    fn test_to_text_empty() {
+        let hex_array = HexArray::new(0, 3, 0);
+        assert_eq!(
+            hex_array.to_text(TextStyle::Ascii, |tile| tile.to_string()),
+            ""
+        );
+    }
+
+    // from_text tests
+
+    #[test]
+    fn test_from_text() {
+        let hex_array = HexArray::<i32>::from_text(
+            "
+                     ___
+                 ___/ 1 \\___
+                / 0 \\___/ 0 \\
+                \\___/ 3 \\___/
+                / 4 \\___/ 2 \\
+                \\___/   \\___/
+            ",
+            |label| label.parse(),
+        )
+        .unwrap();
+        assert_eq!(hex_array.height(), 3);
+        assert_eq!(hex_array.width(), 2);
+        assert_eq!(hex_array.get(0, 1), Some(&0));
+        assert_eq!(hex_array.get(1, 1), Some(&1));
+        assert_eq!(hex_array.get(1, 0), Some(&3));
+        assert_eq!(hex_array.get(0, 0), Some(&4));
+        assert_eq!(hex_array.get(2, 0), Some(&2));
+    }
+
+    #[test]
+    fn test_from_text_round_trip() {
+        let mut hex_array = HexArray::new(4, 3, 0);
+        hex_array.set(3, 2, 42);
+        hex_array.set(0, 0, -1);
+        for style in [TextStyle::Ascii, TextStyle::Unicode] {
+            let text = hex_array.to_text(style, |tile| tile.to_string());
+            let parsed = HexArray::<i32>::from_text(&text, |label| label.parse()).unwrap();
+            assert_eq!(parsed.height(), 4);
+            assert_eq!(parsed.width(), 3);
+            for x in 0..4 {
+                for y in 0..3 {
+                    assert_eq!(parsed.get(x, y), hex_array.get(x, y));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_text_single_column() {
+        let parsed = HexArray::<i32>::from_text(" ___\n/ 7 \\\n\\___/\n", |label| label.parse());
+        let parsed = parsed.unwrap();
+        assert_eq!(parsed.height(), 1);
+        assert_eq!(parsed.width(), 1);
+        assert_eq!(parsed.get(0, 0), Some(&7));
+    }
+
+    #[test]
+    fn test_from_text_invalid_tile() {
+        let result =
+            HexArray::<i32>::from_text(" ___\n/ a \\\n\\___/\n", |label| label.parse::<i32>());
+        assert!(matches!(
+            result,
+            Err(ParseTextError::Tile { x: 0, y: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_from_text_shape() {
+        let result = HexArray::<i32>::from_text("hello", |label| label.parse());
+        assert_eq!(result.unwrap_err(), ParseTextError::Shape);
+    }
+
+    // Debug tests
+
+    #[test]
+    fn test_debug() {
+        let hex_array = HexArray::new(1, 2, 0);
+        assert_eq!(
+            format!("{:?}", hex_array),
+            "HexArray { height: 1, width: 2, tiles: [0, 0] }"
+        );
+        assert!(format!("{:#?}", hex_array).starts_with("HexArray {\n    height: 1,\n"));
+    }
+
+    // Display tests
+
+    #[test]
+    fn test_display() {
+        let hex_array = HexArray::new(1, 1, 'x');
+        assert_eq!(hex_array.to_string(), " ___\n/ x \\\n\\___/\n");
+    }
+}
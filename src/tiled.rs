@@ -0,0 +1,995 @@
+//! Import and export of hexagonal maps made with the Tiled editor, in the TMX (XML) and TMJ
+//! (JSON) formats.
+//!
+//! Tiled stores staggered maps as rows and columns. The tiles of every layer are mapped onto
+//! HexArray indices so that adjacent tiles stay adjacent: with `staggeraxis="x"` the x index is
+//! the Tiled column, with `staggeraxis="y"` it is the Tiled row, and the y index is the other one,
+//! reversed when `staggerindex="even"`.
+//!
+//! Tile layers round-trip with their opacity, visibility, offset and custom properties, and so do
+//! the properties of the map. The rest of a map is not preserved:
+//!
+//! - only external tilesets are supported. Tiled embeds new tilesets in the map by default, and
+//!   such maps are rejected until their tilesets are exported to TSX or TSJ files,
+//! - infinite maps and compressed layer data are rejected,
+//! - object groups, image layers and group layers, including the layers inside groups, are
+//!   skipped when reading,
+//! - members of `class` properties are not kept, only the value of the property itself.
+
+use std::error::Error;
+use std::fmt;
+use std::fmt::Write;
+
+use serde_json::{json, Value};
+
+use crate::base64::decode;
+use crate::HexArray;
+
+/// Flag set on a tile that is flipped horizontally.
+pub const FLIPPED_HORIZONTALLY: u32 = 0x8000_0000;
+/// Flag set on a tile that is flipped vertically.
+pub const FLIPPED_VERTICALLY: u32 = 0x4000_0000;
+/// Flag set on a tile that is flipped diagonally.
+pub const FLIPPED_DIAGONALLY: u32 = 0x2000_0000;
+/// Flag set on a tile that is rotated by 120 degrees.
+pub const ROTATED_HEXAGONAL_120: u32 = 0x1000_0000;
+
+const FLAGS: u32 =
+    FLIPPED_HORIZONTALLY | FLIPPED_VERTICALLY | FLIPPED_DIAGONALLY | ROTATED_HEXAGONAL_120;
+
+/// Get the global tile id of a tile, without its flip flags.
+pub fn gid(tile: u32) -> u32 {
+    tile & !FLAGS
+}
+
+/// Get the flip flags of a tile.
+pub fn flags(tile: u32) -> u32 {
+    tile & FLAGS
+}
+
+/// An error returned when reading a Tiled map.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TiledError {
+    /// The document is not valid XML or JSON.
+    Syntax(String),
+    /// A required attribute or field is missing.
+    Missing(String),
+    /// An attribute or field has an invalid value.
+    Invalid(String),
+    /// The map uses a feature that is not supported.
+    Unsupported(String),
+}
+
+impl fmt::Display for TiledError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TiledError::Syntax(message) => write!(f, "syntax error: {}", message),
+            TiledError::Missing(name) => write!(f, "missing {}", name),
+            TiledError::Invalid(name) => write!(f, "invalid {}", name),
+            TiledError::Unsupported(name) => write!(f, "unsupported {}", name),
+        }
+    }
+}
+
+impl Error for TiledError {}
+
+/// The axis along which rows or columns are staggered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StaggerAxis {
+    /// Columns are staggered, giving flat-topped tiles.
+    X,
+    /// Rows are staggered, giving pointy-topped tiles.
+    Y,
+}
+
+/// Which rows or columns are shifted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StaggerIndex {
+    Odd,
+    Even,
+}
+
+/// A reference to an external tileset. Embedded tilesets are not supported.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Tileset {
+    /// The global tile id of the first tile of the tileset.
+    pub first_gid: u32,
+    /// The path of the tileset file.
+    pub source: String,
+}
+
+/// A custom property of a map or layer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Property {
+    pub name: String,
+    /// The type of the property, such as `string`, `int`, `float`, `bool`, `color` or `file`.
+    pub kind: String,
+    /// The value of the property as written in TMX.
+    pub value: String,
+}
+
+/// A tile layer, with raw tiles including their flip flags.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TiledLayer {
+    pub id: u32,
+    pub name: String,
+    pub tiles: HexArray<u32>,
+    pub opacity: f32,
+    pub visible: bool,
+    /// The horizontal offset of the layer in pixels.
+    pub offset_x: f32,
+    /// The vertical offset of the layer in pixels.
+    pub offset_y: f32,
+    pub properties: Vec<Property>,
+}
+
+impl TiledLayer {
+    /// Create a new visible, opaque layer without offset or properties.
+    pub fn new(id: u32, name: String, tiles: HexArray<u32>) -> Self {
+        TiledLayer {
+            id,
+            name,
+            tiles,
+            opacity: 1.0,
+            visible: true,
+            offset_x: 0.0,
+            offset_y: 0.0,
+            properties: Vec::new(),
+        }
+    }
+}
+
+/// A hexagonal Tiled map.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TiledMap {
+    /// The number of Tiled columns.
+    pub columns: usize,
+    /// The number of Tiled rows.
+    pub rows: usize,
+    pub tile_width: u32,
+    pub tile_height: u32,
+    pub hex_side_length: u32,
+    pub stagger_axis: StaggerAxis,
+    pub stagger_index: StaggerIndex,
+    pub tilesets: Vec<Tileset>,
+    pub layers: Vec<TiledLayer>,
+    pub properties: Vec<Property>,
+}
+
+impl TiledMap {
+    /// Get the height and width of the HexArray of a layer.
+    pub fn dimensions(&self) -> (usize, usize) {
+        match self.stagger_axis {
+            StaggerAxis::X => (self.columns, self.rows),
+            StaggerAxis::Y => (self.rows, self.columns),
+        }
+    }
+
+    /// Get the Tiled column and row of the tile at the given HexArray indices.
+    pub fn to_tiled(&self, x: usize, y: usize) -> (usize, usize) {
+        let (_, width) = self.dimensions();
+        let y = match self.stagger_index {
+            StaggerIndex::Odd => y,
+            StaggerIndex::Even => width - 1 - y,
+        };
+        match self.stagger_axis {
+            StaggerAxis::X => (x, y),
+            StaggerAxis::Y => (y, x),
+        }
+    }
+
+    /// Get the HexArray indices of the tile at the given Tiled column and row.
+    pub fn from_tiled(&self, column: usize, row: usize) -> (usize, usize) {
+        let (_, width) = self.dimensions();
+        let (x, y) = match self.stagger_axis {
+            StaggerAxis::X => (column, row),
+            StaggerAxis::Y => (row, column),
+        };
+        match self.stagger_index {
+            StaggerIndex::Odd => (x, y),
+            StaggerIndex::Even => (x, width - 1 - y),
+        }
+    }
+
+    /// Get the number of tiles of a layer, checking that it fits in memory.
+    fn size(&self) -> Result<usize, TiledError> {
+        self.columns
+            .checked_mul(self.rows)
+            .ok_or_else(|| TiledError::Invalid("map size".to_string()))
+    }
+
+    fn layer_from_data(
+        &self,
+        id: u32,
+        name: String,
+        data: Vec<u32>,
+    ) -> Result<TiledLayer, TiledError> {
+        if data.len() != self.size()? {
+            return Err(TiledError::Invalid(format!(
+                "data length of layer {}",
+                name
+            )));
+        }
+        let (height, width) = self.dimensions();
+        let mut tiles = HexArray::new(height, width, 0);
+        for (index, tile) in data.into_iter().enumerate() {
+            let (x, y) = self.from_tiled(index % self.columns, index / self.columns);
+            tiles.tiles[x * width + y] = tile;
+        }
+        Ok(TiledLayer::new(id, name, tiles))
+    }
+
+    fn layer_data(&self, layer: &TiledLayer) -> Vec<u32> {
+        let mut data = vec![0; self.columns * self.rows];
+        for x in 0..layer.tiles.height {
+            for y in 0..layer.tiles.width {
+                let (column, row) = self.to_tiled(x, y);
+                data[row * self.columns + column] = layer.tiles.tiles[x * layer.tiles.width + y];
+            }
+        }
+        data
+    }
+
+    fn check_layers(&self) -> Result<(), TiledError> {
+        self.size()?;
+        let (height, width) = self.dimensions();
+        for layer in &self.layers {
+            if layer.tiles.height != height || layer.tiles.width != width {
+                return Err(TiledError::Invalid(format!(
+                    "dimensions of layer {}",
+                    layer.name
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn next_layer_id(&self) -> u32 {
+        self.layers.iter().map(|layer| layer.id).max().unwrap_or(0) + 1
+    }
+
+    /// Read a map from a TMX document.
+    ///
+    /// Returns `TiledError::Unsupported` if the map is not hexagonal, is infinite, embeds a tileset
+    /// or compresses its layers.
+    pub fn from_tmx(text: &str) -> Result<Self, TiledError> {
+        let document = roxmltree::Document::parse(text)
+            .map_err(|error| TiledError::Syntax(error.to_string()))?;
+        let root = document.root_element();
+        if !root.has_tag_name("map") {
+            return Err(TiledError::Missing("map element".to_string()));
+        }
+        if xml_attribute(root, "orientation")? != "hexagonal" {
+            return Err(TiledError::Unsupported("orientation".to_string()));
+        }
+        if root.attribute("infinite").unwrap_or("0") != "0" {
+            return Err(TiledError::Unsupported("infinite map".to_string()));
+        }
+        let mut map = TiledMap {
+            columns: xml_number(root, "width")?,
+            rows: xml_number(root, "height")?,
+            tile_width: xml_number(root, "tilewidth")?,
+            tile_height: xml_number(root, "tileheight")?,
+            hex_side_length: xml_number(root, "hexsidelength")?,
+            stagger_axis: parse_axis(xml_attribute(root, "staggeraxis")?)?,
+            stagger_index: parse_index(xml_attribute(root, "staggerindex")?)?,
+            tilesets: Vec::new(),
+            layers: Vec::new(),
+            properties: xml_properties(root)?,
+        };
+        map.size()?;
+        for node in root.children().filter(|node| node.is_element()) {
+            match node.tag_name().name() {
+                "tileset" => {
+                    let source = node
+                        .attribute("source")
+                        .ok_or_else(|| TiledError::Unsupported("embedded tileset".to_string()))?;
+                    map.tilesets.push(Tileset {
+                        first_gid: xml_number(node, "firstgid")?,
+                        source: source.to_string(),
+                    });
+                }
+                "layer" => {
+                    let name = node.attribute("name").unwrap_or_default().to_string();
+                    let id = xml_number(node, "id")?;
+                    let data = node
+                        .children()
+                        .find(|child| child.has_tag_name("data"))
+                        .ok_or_else(|| TiledError::Missing(format!("data of layer {}", name)))?;
+                    let tiles = xml_data(data)?;
+                    let mut layer = map.layer_from_data(id, name, tiles)?;
+                    layer.opacity = xml_optional(node, "opacity", 1.0)?;
+                    layer.visible = xml_optional(node, "visible", 1u8)? != 0;
+                    layer.offset_x = xml_optional(node, "offsetx", 0.0)?;
+                    layer.offset_y = xml_optional(node, "offsety", 0.0)?;
+                    layer.properties = xml_properties(node)?;
+                    map.layers.push(layer);
+                }
+                _ => {}
+            }
+        }
+        Ok(map)
+    }
+
+    /// Write the map as a TMX document.
+    ///
+    /// Returns an error if a layer does not match the dimensions of the map.
+    pub fn to_tmx(&self) -> Result<String, TiledError> {
+        self.check_layers()?;
+        let mut tmx = String::new();
+        let _ = writeln!(tmx, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>");
+        let _ = writeln!(
+            tmx,
+            "<map version=\"1.10\" orientation=\"hexagonal\" renderorder=\"right-down\" width=\"{}\" height=\"{}\" tilewidth=\"{}\" tileheight=\"{}\" hexsidelength=\"{}\" staggeraxis=\"{}\" staggerindex=\"{}\" infinite=\"0\" nextlayerid=\"{}\" nextobjectid=\"1\">",
+            self.columns,
+            self.rows,
+            self.tile_width,
+            self.tile_height,
+            self.hex_side_length,
+            axis_name(self.stagger_axis),
+            index_name(self.stagger_index),
+            self.next_layer_id()
+        );
+        write_properties(&mut tmx, &self.properties, " ");
+        for tileset in &self.tilesets {
+            let _ = writeln!(
+                tmx,
+                " <tileset firstgid=\"{}\" source=\"{}\"/>",
+                tileset.first_gid,
+                escape(&tileset.source)
+            );
+        }
+        for layer in &self.layers {
+            let _ = write!(
+                tmx,
+                " <layer id=\"{}\" name=\"{}\" width=\"{}\" height=\"{}\"",
+                layer.id,
+                escape(&layer.name),
+                self.columns,
+                self.rows
+            );
+            if layer.opacity != 1.0 {
+                let _ = write!(tmx, " opacity=\"{}\"", layer.opacity);
+            }
+            if !layer.visible {
+                let _ = write!(tmx, " visible=\"0\"");
+            }
+            if layer.offset_x != 0.0 {
+                let _ = write!(tmx, " offsetx=\"{}\"", layer.offset_x);
+            }
+            if layer.offset_y != 0.0 {
+                let _ = write!(tmx, " offsety=\"{}\"", layer.offset_y);
+            }
+            let _ = writeln!(tmx, ">");
+            write_properties(&mut tmx, &layer.properties, "  ");
+            let _ = writeln!(tmx, "  <data encoding=\"csv\">");
+            let data = self.layer_data(layer);
+            for row in 0..self.rows {
+                let line: Vec<String> = data[row * self.columns..(row + 1) * self.columns]
+                    .iter()
+                    .map(u32::to_string)
+                    .collect();
+                let separator = if row + 1 < self.rows { "," } else { "" };
+                let _ = writeln!(tmx, "{}{}", line.join(","), separator);
+            }
+            let _ = writeln!(tmx, "</data>");
+            let _ = writeln!(tmx, " </layer>");
+        }
+        let _ = writeln!(tmx, "</map>");
+        Ok(tmx)
+    }
+
+    /// Read a map from a TMJ document.
+    ///
+    /// Returns `TiledError::Unsupported` if the map is not hexagonal, is infinite, embeds a tileset
+    /// or compresses its layers.
+    pub fn from_tmj(text: &str) -> Result<Self, TiledError> {
+        let root: Value =
+            serde_json::from_str(text).map_err(|error| TiledError::Syntax(error.to_string()))?;
+        if json_str(&root, "orientation")? != "hexagonal" {
+            return Err(TiledError::Unsupported("orientation".to_string()));
+        }
+        if root
+            .get("infinite")
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+        {
+            return Err(TiledError::Unsupported("infinite map".to_string()));
+        }
+        let mut map = TiledMap {
+            columns: json_number(&root, "width")?,
+            rows: json_number(&root, "height")?,
+            tile_width: json_number(&root, "tilewidth")?,
+            tile_height: json_number(&root, "tileheight")?,
+            hex_side_length: json_number(&root, "hexsidelength")?,
+            stagger_axis: parse_axis(json_str(&root, "staggeraxis")?)?,
+            stagger_index: parse_index(json_str(&root, "staggerindex")?)?,
+            tilesets: Vec::new(),
+            layers: Vec::new(),
+            properties: json_properties(&root)?,
+        };
+        map.size()?;
+        for tileset in json_array(&root, "tilesets")? {
+            let source = tileset
+                .get("source")
+                .and_then(Value::as_str)
+                .ok_or_else(|| TiledError::Unsupported("embedded tileset".to_string()))?;
+            map.tilesets.push(Tileset {
+                first_gid: json_number(tileset, "firstgid")?,
+                source: source.to_string(),
+            });
+        }
+        for layer in json_array(&root, "layers")? {
+            if layer.get("type").and_then(Value::as_str) != Some("tilelayer") {
+                continue;
+            }
+            let name = layer
+                .get("name")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string();
+            let id = json_number(layer, "id")?;
+            let data = json_data(layer, &name)?;
+            let properties = json_properties(layer)?;
+            let opacity = layer.get("opacity").and_then(Value::as_f64).unwrap_or(1.0);
+            let visible = layer
+                .get("visible")
+                .and_then(Value::as_bool)
+                .unwrap_or(true);
+            let offset_x = layer.get("offsetx").and_then(Value::as_f64).unwrap_or(0.0);
+            let offset_y = layer.get("offsety").and_then(Value::as_f64).unwrap_or(0.0);
+            let mut layer = map.layer_from_data(id, name, data)?;
+            layer.opacity = opacity as f32;
+            layer.visible = visible;
+            layer.offset_x = offset_x as f32;
+            layer.offset_y = offset_y as f32;
+            layer.properties = properties;
+            map.layers.push(layer);
+        }
+        Ok(map)
+    }
+
+    /// Write the map as a TMJ document.
+    ///
+    /// Returns an error if a layer does not match the dimensions of the map.
+    pub fn to_tmj(&self) -> Result<String, TiledError> {
+        self.check_layers()?;
+        let tilesets: Vec<Value> = self
+            .tilesets
+            .iter()
+            .map(|tileset| json!({ "firstgid": tileset.first_gid, "source": tileset.source }))
+            .collect();
+        let layers: Vec<Value> = self
+            .layers
+            .iter()
+            .map(|layer| {
+                let mut value = json!({
+                    "type": "tilelayer",
+                    "id": layer.id,
+                    "name": layer.name,
+                    "width": self.columns,
+                    "height": self.rows,
+                    "x": 0,
+                    "y": 0,
+                    "opacity": layer.opacity,
+                    "visible": layer.visible,
+                    "data": self.layer_data(layer),
+                });
+                if layer.offset_x != 0.0 {
+                    value["offsetx"] = json!(layer.offset_x);
+                }
+                if layer.offset_y != 0.0 {
+                    value["offsety"] = json!(layer.offset_y);
+                }
+                if !layer.properties.is_empty() {
+                    value["properties"] = json_property_values(&layer.properties);
+                }
+                value
+            })
+            .collect();
+        let mut map = json!({
+            "type": "map",
+            "version": "1.10",
+            "orientation": "hexagonal",
+            "renderorder": "right-down",
+            "width": self.columns,
+            "height": self.rows,
+            "tilewidth": self.tile_width,
+            "tileheight": self.tile_height,
+            "hexsidelength": self.hex_side_length,
+            "staggeraxis": axis_name(self.stagger_axis),
+            "staggerindex": index_name(self.stagger_index),
+            "infinite": false,
+            "nextlayerid": self.next_layer_id(),
+            "nextobjectid": 1,
+            "tilesets": tilesets,
+            "layers": layers,
+        });
+        if !self.properties.is_empty() {
+            map["properties"] = json_property_values(&self.properties);
+        }
+        Ok(serde_json::to_string_pretty(&map).unwrap())
+    }
+}
+
+fn parse_axis(value: &str) -> Result<StaggerAxis, TiledError> {
+    match value {
+        "x" => Ok(StaggerAxis::X),
+        "y" => Ok(StaggerAxis::Y),
+        _ => Err(TiledError::Invalid("staggeraxis".to_string())),
+    }
+}
+
+fn parse_index(value: &str) -> Result<StaggerIndex, TiledError> {
+    match value {
+        "odd" => Ok(StaggerIndex::Odd),
+        "even" => Ok(StaggerIndex::Even),
+        _ => Err(TiledError::Invalid("staggerindex".to_string())),
+    }
+}
+
+fn axis_name(axis: StaggerAxis) -> &'static str {
+    match axis {
+        StaggerAxis::X => "x",
+        StaggerAxis::Y => "y",
+    }
+}
+
+fn index_name(index: StaggerIndex) -> &'static str {
+    match index {
+        StaggerIndex::Odd => "odd",
+        StaggerIndex::Even => "even",
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\n', "&#10;")
+}
+
+fn write_properties(tmx: &mut String, properties: &[Property], indent: &str) {
+    if properties.is_empty() {
+        return;
+    }
+    let _ = writeln!(tmx, "{}<properties>", indent);
+    for property in properties {
+        let _ = write!(
+            tmx,
+            "{} <property name=\"{}\"",
+            indent,
+            escape(&property.name)
+        );
+        if property.kind != "string" {
+            let _ = write!(tmx, " type=\"{}\"", escape(&property.kind));
+        }
+        let _ = writeln!(tmx, " value=\"{}\"/>", escape(&property.value));
+    }
+    let _ = writeln!(tmx, "{}</properties>", indent);
+}
+
+fn xml_attribute<'a>(node: roxmltree::Node<'a, '_>, name: &str) -> Result<&'a str, TiledError> {
+    node.attribute(name)
+        .ok_or_else(|| TiledError::Missing(name.to_string()))
+}
+
+fn xml_number<T>(node: roxmltree::Node, name: &str) -> Result<T, TiledError>
+where
+    T: std::str::FromStr,
+{
+    xml_attribute(node, name)?
+        .trim()
+        .parse()
+        .map_err(|_| TiledError::Invalid(name.to_string()))
+}
+
+fn xml_optional<T>(node: roxmltree::Node, name: &str, default: T) -> Result<T, TiledError>
+where
+    T: std::str::FromStr,
+{
+    match node.attribute(name) {
+        Some(_) => xml_number(node, name),
+        None => Ok(default),
+    }
+}
+
+fn xml_properties(node: roxmltree::Node) -> Result<Vec<Property>, TiledError> {
+    let Some(properties) = node
+        .children()
+        .find(|child| child.has_tag_name("properties"))
+    else {
+        return Ok(Vec::new());
+    };
+    properties
+        .children()
+        .filter(|child| child.has_tag_name("property"))
+        .map(|property| {
+            Ok(Property {
+                name: xml_attribute(property, "name")?.to_string(),
+                kind: property.attribute("type").unwrap_or("string").to_string(),
+                // multi-line strings are stored as text instead of an attribute
+                value: property
+                    .attribute("value")
+                    .or_else(|| property.text())
+                    .unwrap_or_default()
+                    .to_string(),
+            })
+        })
+        .collect()
+}
+
+fn xml_data(data: roxmltree::Node) -> Result<Vec<u32>, TiledError> {
+    if data.attribute("compression").is_some() {
+        return Err(TiledError::Unsupported("compression".to_string()));
+    }
+    let text = data.text().unwrap_or_default();
+    match data.attribute("encoding") {
+        Some("csv") => text
+            .split(',')
+            .map(|tile| {
+                tile.trim()
+                    .parse()
+                    .map_err(|_| TiledError::Invalid("csv data".to_string()))
+            })
+            .collect(),
+        Some("base64") => base64_data(text.trim()),
+        Some(_) => Err(TiledError::Unsupported("encoding".to_string())),
+        None => data
+            .children()
+            .filter(|child| child.has_tag_name("tile"))
+            .map(|tile| match tile.attribute("gid") {
+                Some(_) => xml_number(tile, "gid"),
+                None => Ok(0),
+            })
+            .collect(),
+    }
+}
+
+fn base64_data(text: &str) -> Result<Vec<u32>, TiledError> {
+    let bytes = decode(text).ok_or_else(|| TiledError::Invalid("base64 data".to_string()))?;
+    if bytes.len() % 4 != 0 {
+        return Err(TiledError::Invalid("base64 data".to_string()));
+    }
+    Ok(bytes
+        .chunks_exact(4)
+        .map(|chunk| u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect())
+}
+
+fn json_field<'a>(value: &'a Value, name: &str) -> Result<&'a Value, TiledError> {
+    value
+        .get(name)
+        .ok_or_else(|| TiledError::Missing(name.to_string()))
+}
+
+fn json_str<'a>(value: &'a Value, name: &str) -> Result<&'a str, TiledError> {
+    json_field(value, name)?
+        .as_str()
+        .ok_or_else(|| TiledError::Invalid(name.to_string()))
+}
+
+fn json_number<T>(value: &Value, name: &str) -> Result<T, TiledError>
+where
+    T: TryFrom<u64>,
+{
+    json_field(value, name)?
+        .as_u64()
+        .and_then(|number| T::try_from(number).ok())
+        .ok_or_else(|| TiledError::Invalid(name.to_string()))
+}
+
+fn json_array<'a>(value: &'a Value, name: &str) -> Result<&'a Vec<Value>, TiledError> {
+    match value.get(name) {
+        Some(array) => array
+            .as_array()
+            .ok_or_else(|| TiledError::Invalid(name.to_string())),
+        None => Ok(const { &Vec::new() }),
+    }
+}
+
+fn json_properties(value: &Value) -> Result<Vec<Property>, TiledError> {
+    json_array(value, "properties")?
+        .iter()
+        .map(|property| {
+            Ok(Property {
+                name: json_str(property, "name")?.to_string(),
+                kind: match property.get("type") {
+                    Some(_) => json_str(property, "type")?.to_string(),
+                    None => "string".to_string(),
+                },
+                value: match json_field(property, "value")? {
+                    Value::String(value) => value.clone(),
+                    value => value.to_string(),
+                },
+            })
+        })
+        .collect()
+}
+
+fn json_property_values(properties: &[Property]) -> Value {
+    let properties: Vec<Value> = properties
+        .iter()
+        .map(|property| {
+            let value = match property.kind.as_str() {
+                "string" | "color" | "file" => Value::String(property.value.clone()),
+                _ => serde_json::from_str(&property.value)
+                    .unwrap_or_else(|_| Value::String(property.value.clone())),
+            };
+            json!({ "name": property.name, "type": property.kind, "value": value })
+        })
+        .collect();
+    Value::Array(properties)
+}
+
+fn json_data(layer: &Value, name: &str) -> Result<Vec<u32>, TiledError> {
+    if layer
+        .get("compression")
+        .and_then(Value::as_str)
+        .is_some_and(|c| !c.is_empty())
+    {
+        return Err(TiledError::Unsupported("compression".to_string()));
+    }
+    let data = layer
+        .get("data")
+        .ok_or_else(|| TiledError::Missing(format!("data of layer {}", name)))?;
+    match data {
+        Value::String(text) => base64_data(text),
+        Value::Array(tiles) => tiles
+            .iter()
+            .map(|tile| {
+                tile.as_u64()
+                    .and_then(|tile| u32::try_from(tile).ok())
+                    .ok_or_else(|| TiledError::Invalid(format!("data of layer {}", name)))
+            })
+            .collect(),
+        _ => Err(TiledError::Invalid(format!("data of layer {}", name))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TMX: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<map version="1.10" orientation="hexagonal" renderorder="right-down" width="3" height="2" tilewidth="32" tileheight="28" hexsidelength="16" staggeraxis="x" staggerindex="odd" infinite="0" nextlayerid="2" nextobjectid="1">
+ <tileset firstgid="1" source="terrain.tsx"/>
+ <layer id="1" name="Ground" width="3" height="2">
+  <data encoding="csv">
+1,2,3,
+4,5,2147483654
+</data>
+ </layer>
+</map>
+"#;
+
+    // gid tests
+
+    #[test]
+    fn test_gid() {
+        let tile = 6 | FLIPPED_HORIZONTALLY | ROTATED_HEXAGONAL_120;
+        assert_eq!(gid(tile), 6);
+        assert_eq!(flags(tile), FLIPPED_HORIZONTALLY | ROTATED_HEXAGONAL_120);
+    }
+
+    // from_tmx tests
+
+    #[test]
+    fn test_from_tmx() {
+        let map = TiledMap::from_tmx(TMX).unwrap();
+        assert_eq!((map.columns, map.rows), (3, 2));
+        assert_eq!(map.hex_side_length, 16);
+        assert_eq!(map.stagger_axis, StaggerAxis::X);
+        assert_eq!(map.stagger_index, StaggerIndex::Odd);
+        assert_eq!(
+            map.tilesets,
+            vec![Tileset {
+                first_gid: 1,
+                source: "terrain.tsx".to_string()
+            }]
+        );
+        let layer = &map.layers[0];
+        assert_eq!(layer.name, "Ground");
+        assert_eq!(layer.tiles.height(), 3);
+        assert_eq!(layer.tiles.width(), 2);
+        assert_eq!(layer.tiles.get(0, 0), Some(&1));
+        assert_eq!(layer.tiles.get(2, 0), Some(&3));
+        assert_eq!(layer.tiles.get(0, 1), Some(&4));
+        assert_eq!(gid(*layer.tiles.get(2, 1).unwrap()), 6);
+    }
+
+    #[test]
+    fn test_from_tmx_encodings() {
+        let base64 = TMX.replace(
+            "<data encoding=\"csv\">\n1,2,3,\n4,5,2147483654\n</data>",
+            "<data encoding=\"base64\">AQAAAAIAAAADAAAABAAAAAUAAAAGAACA</data>",
+        );
+        let xml = TMX.replace(
+            "<data encoding=\"csv\">\n1,2,3,\n4,5,2147483654\n</data>",
+            "<data><tile gid=\"1\"/><tile gid=\"2\"/><tile gid=\"3\"/><tile gid=\"4\"/><tile gid=\"5\"/><tile gid=\"2147483654\"/></data>",
+        );
+        let expected = TiledMap::from_tmx(TMX).unwrap();
+        assert_eq!(TiledMap::from_tmx(&base64).unwrap(), expected);
+        assert_eq!(TiledMap::from_tmx(&xml).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_from_tmx_errors() {
+        assert!(matches!(
+            TiledMap::from_tmx("<map"),
+            Err(TiledError::Syntax(_))
+        ));
+        assert_eq!(
+            TiledMap::from_tmx(&TMX.replace("hexagonal", "orthogonal")),
+            Err(TiledError::Unsupported("orientation".to_string()))
+        );
+        assert_eq!(
+            TiledMap::from_tmx(&TMX.replace("1,2,3,", "1,2,")),
+            Err(TiledError::Invalid(
+                "data length of layer Ground".to_string()
+            ))
+        );
+        assert_eq!(
+            TiledMap::from_tmx(&TMX.replace(" staggeraxis=\"x\"", "")),
+            Err(TiledError::Missing("staggeraxis".to_string()))
+        );
+        assert_eq!(
+            TiledMap::from_tmx(&TMX.replace(
+                "width=\"3\" height=\"2\" tilewidth",
+                "width=\"4294967296\" height=\"4294967296\" tilewidth"
+            )),
+            Err(TiledError::Invalid("map size".to_string()))
+        );
+    }
+
+    // to_tmx tests
+
+    #[test]
+    fn test_to_tmx() {
+        let map = TiledMap::from_tmx(TMX).unwrap();
+        assert_eq!(map.to_tmx().unwrap(), TMX);
+    }
+
+    #[test]
+    fn test_to_tmx_errors() {
+        let mut map = TiledMap::from_tmx(TMX).unwrap();
+        map.columns = 4;
+        assert_eq!(
+            map.to_tmx(),
+            Err(TiledError::Invalid(
+                "dimensions of layer Ground".to_string()
+            ))
+        );
+        assert!(map.to_tmj().is_err());
+        map.columns = usize::MAX;
+        assert_eq!(
+            map.to_tmx(),
+            Err(TiledError::Invalid("map size".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_round_trip_attributes() {
+        let tmx = TMX
+            .replace(
+                "nextobjectid=\"1\">\n",
+                "nextobjectid=\"1\">\n <properties>\n  <property name=\"seed\" type=\"int\" value=\"42\"/>\n </properties>\n",
+            )
+            .replace(
+                "height=\"2\">\n",
+                "height=\"2\" opacity=\"0.5\" visible=\"0\" offsetx=\"8\" offsety=\"-4\">\n  <properties>\n   <property name=\"note\" value=\"a &amp; b&#10;c\"/>\n  </properties>\n",
+            );
+        let map = TiledMap::from_tmx(&tmx).unwrap();
+        let layer = &map.layers[0];
+        assert_eq!(layer.opacity, 0.5);
+        assert!(!layer.visible);
+        assert_eq!((layer.offset_x, layer.offset_y), (8.0, -4.0));
+        assert_eq!(layer.properties[0].value, "a & b\nc");
+        assert_eq!(map.properties[0].kind, "int");
+        assert_eq!(map.to_tmx().unwrap(), tmx);
+        let tmj = map.to_tmj().unwrap();
+        assert!(tmj.contains("\"value\": 42"));
+        assert_eq!(TiledMap::from_tmj(&tmj).unwrap(), map);
+    }
+
+    // stagger tests
+
+    #[test]
+    fn test_stagger_adjacency() {
+        // Tiled neighbors of column 1, row 1 in a 4x4 map for every stagger setting.
+        let cases = [
+            (
+                StaggerAxis::X,
+                StaggerIndex::Odd,
+                [(1, 0), (0, 1), (2, 1), (0, 2), (2, 2), (1, 2)],
+            ),
+            (
+                StaggerAxis::X,
+                StaggerIndex::Even,
+                [(1, 0), (0, 0), (2, 0), (0, 1), (2, 1), (1, 2)],
+            ),
+            (
+                StaggerAxis::Y,
+                StaggerIndex::Odd,
+                [(0, 1), (2, 1), (1, 0), (2, 0), (1, 2), (2, 2)],
+            ),
+            (
+                StaggerAxis::Y,
+                StaggerIndex::Even,
+                [(0, 1), (2, 1), (0, 0), (1, 0), (0, 2), (1, 2)],
+            ),
+        ];
+        for (stagger_axis, stagger_index, neighbors) in cases {
+            let map = TiledMap {
+                columns: 4,
+                rows: 4,
+                tile_width: 32,
+                tile_height: 32,
+                hex_side_length: 16,
+                stagger_axis,
+                stagger_index,
+                tilesets: Vec::new(),
+                layers: Vec::new(),
+                properties: Vec::new(),
+            };
+            let hex_array = HexArray::new(4, 4, 0);
+            let (x, y) = map.from_tiled(1, 1);
+            let mut adjacent: Vec<_> = hex_array
+                .adjacent(x, y)
+                .into_iter()
+                .map(|(x, y)| map.to_tiled(x, y))
+                .collect();
+            let mut neighbors = neighbors.to_vec();
+            adjacent.sort();
+            neighbors.sort();
+            assert_eq!(adjacent, neighbors);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_y_even() {
+        let tmx = TMX.replace(
+            "staggeraxis=\"x\" staggerindex=\"odd\"",
+            "staggeraxis=\"y\" staggerindex=\"even\"",
+        );
+        let map = TiledMap::from_tmx(&tmx).unwrap();
+        assert_eq!(map.layers[0].tiles.height(), 2);
+        assert_eq!(map.layers[0].tiles.width(), 3);
+        assert_eq!(map.layers[0].tiles.get(0, 2), Some(&1));
+        assert_eq!(map.to_tmx().unwrap(), tmx);
+    }
+
+    // tmj tests
+
+    #[test]
+    fn test_tmj_round_trip() {
+        let map = TiledMap::from_tmx(TMX).unwrap();
+        let tmj = map.to_tmj().unwrap();
+        assert_eq!(TiledMap::from_tmj(&tmj).unwrap(), map);
+    }
+
+    #[test]
+    fn test_from_tmj() {
+        let tmj = r#"{
+            "orientation": "hexagonal", "width": 2, "height": 1,
+            "tilewidth": 32, "tileheight": 28, "hexsidelength": 16,
+            "staggeraxis": "x", "staggerindex": "odd", "infinite": false,
+            "tilesets": [{ "firstgid": 1, "source": "terrain.tsj" }],
+            "layers": [
+                { "type": "objectgroup", "id": 2, "name": "Units", "objects": [] },
+                { "type": "tilelayer", "id": 1, "name": "Ground", "width": 2, "height": 1, "data": [7, 8] }
+            ]
+        }"#;
+        let map = TiledMap::from_tmj(tmj).unwrap();
+        assert_eq!(map.layers.len(), 1);
+        assert_eq!(map.layers[0].tiles.get(0, 0), Some(&7));
+        assert_eq!(map.layers[0].tiles.get(1, 0), Some(&8));
+        assert_eq!(
+            TiledMap::from_tmj(
+                &tmj.replace("\"source\": \"terrain.tsj\"", "\"name\": \"terrain\"")
+            ),
+            Err(TiledError::Unsupported("embedded tileset".to_string()))
+        );
+    }
+}
@@ -0,0 +1,206 @@
+use alloc::vec::Vec;
+use core::ops::{Deref, Range};
+
+use crate::{HexArray, HexBitArray, HexGrid};
+
+/// A HexArray that records which tiles were modified since the changes were last taken.
+///
+/// Reading goes through `Deref` to the HexArray; every mutable access through `get_mut`, `set` or
+/// `HexGrid` marks the tile as changed, whether or not the value actually differs.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TrackedHexArray<T> {
+    hex_array: HexArray<T>,
+    marked: HexBitArray,
+    changes: Changes,
+}
+
+impl<T> TrackedHexArray<T> {
+    /// Start tracking changes to the given HexArray.
+    pub fn new(hex_array: HexArray<T>) -> Self {
+        TrackedHexArray {
+            marked: HexBitArray::new(hex_array.height, hex_array.width, false),
+            hex_array,
+            changes: Changes::default(),
+        }
+    }
+
+    /// Stop tracking changes and get the HexArray.
+    pub fn into_inner(self) -> HexArray<T> {
+        self.hex_array
+    }
+
+    /// Mark the tile at the given indices as changed.
+    pub fn mark(&mut self, x: usize, y: usize) -> Option<()> {
+        if self.marked.get(x, y)? {
+            return Some(());
+        }
+        self.marked.set(x, y, true);
+        self.changes.tiles.push((x, y));
+        self.changes.bounds = Some(match self.changes.bounds.take() {
+            Some((xs, ys)) => (
+                xs.start.min(x)..xs.end.max(x + 1),
+                ys.start.min(y)..ys.end.max(y + 1),
+            ),
+            None => (x..x + 1, y..y + 1),
+        });
+        Some(())
+    }
+
+    /// Get a mutable reference to the value at the given indices, marking it as changed.
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        self.mark(x, y)?;
+        self.hex_array.get_mut(x, y)
+    }
+
+    /// Set the value at the given indices, marking it as changed.
+    pub fn set(&mut self, x: usize, y: usize, value: T) -> Option<()> {
+        *self.get_mut(x, y)? = value;
+        Some(())
+    }
+
+    /// Check whether any tile changed since the changes were last taken.
+    pub fn has_changes(&self) -> bool {
+        !self.changes.is_empty()
+    }
+
+    /// Get the changes since they were last taken.
+    pub fn changes(&self) -> &Changes {
+        &self.changes
+    }
+
+    /// Get the changes since they were last taken and start recording anew.
+    pub fn take_changes(&mut self) -> Changes {
+        for &(x, y) in &self.changes.tiles {
+            self.marked.set(x, y, false);
+        }
+        core::mem::take(&mut self.changes)
+    }
+}
+
+impl<T> Deref for TrackedHexArray<T> {
+    type Target = HexArray<T>;
+
+    fn deref(&self) -> &HexArray<T> {
+        &self.hex_array
+    }
+}
+
+impl<T> From<HexArray<T>> for TrackedHexArray<T> {
+    fn from(hex_array: HexArray<T>) -> Self {
+        TrackedHexArray::new(hex_array)
+    }
+}
+
+impl<T> HexGrid for TrackedHexArray<T> {
+    type Tile = T;
+
+    fn height(&self) -> usize {
+        self.hex_array.height
+    }
+
+    fn width(&self) -> usize {
+        self.hex_array.width
+    }
+
+    fn get(&self, x: usize, y: usize) -> Option<&T> {
+        self.hex_array.get(x, y)
+    }
+
+    fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        TrackedHexArray::get_mut(self, x, y)
+    }
+}
+
+/// The tiles of a TrackedHexArray modified since the changes were last taken.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Changes {
+    tiles: Vec<(usize, usize)>,
+    bounds: Option<(Range<usize>, Range<usize>)>,
+}
+
+impl Changes {
+    /// Get the indices of the changed tiles, in the order they were first changed.
+    pub fn tiles(&self) -> &[(usize, usize)] {
+        &self.tiles
+    }
+
+    /// Get the number of changed tiles.
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    /// Check whether no tile changed.
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    /// Get the ranges of x and y indices of the smallest rectangle containing every changed tile.
+    pub fn bounds(&self) -> Option<(Range<usize>, Range<usize>)> {
+        self.bounds.clone()
+    }
+
+    /// Get the sorted indices of the chunks containing a changed tile.
+    ///
+    /// Chunk `(cx, cy)` covers x indices from `cx * chunk_height` and y indices from
+    /// `cy * chunk_width`. Returns `None` if either chunk dimension is zero.
+    pub fn chunks(&self, chunk_height: usize, chunk_width: usize) -> Option<Vec<(usize, usize)>> {
+        if chunk_height == 0 || chunk_width == 0 {
+            return None;
+        }
+        let mut chunks: Vec<_> = self
+            .tiles
+            .iter()
+            .map(|(x, y)| (x / chunk_height, y / chunk_width))
+            .collect();
+        chunks.sort_unstable();
+        chunks.dedup();
+        Some(chunks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // change tests
+
+    #[test]
+    fn test_changes() {
+        let mut tracked = TrackedHexArray::new(HexArray::new(8, 8, 0));
+        assert!(!tracked.has_changes());
+        assert_eq!(tracked.set(5, 1, 1), Some(()));
+        *tracked.get_mut(2, 6).unwrap() += 1;
+        tracked.set(5, 1, 2);
+        assert_eq!(tracked.set(8, 0, 1), None);
+        assert_eq!(tracked.get(5, 1), Some(&2));
+        let changes = tracked.take_changes();
+        assert_eq!(changes.tiles(), [(5, 1), (2, 6)]);
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes.bounds(), Some((2..6, 1..7)));
+        assert!(!tracked.has_changes());
+        assert_eq!(tracked.changes().bounds(), None);
+        tracked.set(5, 1, 3);
+        assert_eq!(tracked.changes().tiles(), [(5, 1)]);
+        assert_eq!(tracked.into_inner().get(5, 1), Some(&3));
+    }
+
+    #[test]
+    fn test_chunks() {
+        let mut tracked = TrackedHexArray::from(HexArray::new(8, 8, 0));
+        for (x, y) in [(7, 7), (0, 0), (1, 3), (4, 0), (6, 5)] {
+            tracked.set(x, y, 1);
+        }
+        let changes = tracked.take_changes();
+        assert_eq!(changes.chunks(4, 4), Some(vec![(0, 0), (1, 0), (1, 1)]));
+        assert_eq!(changes.chunks(0, 4), None);
+    }
+
+    #[test]
+    fn test_hex_grid() {
+        let mut tracked = TrackedHexArray::new(HexArray::new(3, 3, 0));
+        *HexGrid::get_mut(&mut tracked, 1, 1).unwrap() = 1;
+        let distances = tracked.distance_field([(0, 0)], |_| true);
+        assert_eq!(distances.get(2, 2), Some(&Some(3)));
+        assert_eq!(tracked.changes().tiles(), [(1, 1)]);
+    }
+}
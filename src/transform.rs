@@ -0,0 +1,209 @@
+use crate::{axial_range, from_axial, to_axial, HexArray};
+
+/// How tiles outside the HexArray are handled when sampling a neighborhood.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Border {
+    /// Use the value of the nearest tile inside the HexArray.
+    Clamp,
+    /// Ignore tiles outside the HexArray.
+    Skip,
+    /// Wrap around to the opposite side of the HexArray.
+    ///
+    /// With an odd height, the last and first columns wrapped next to each other are both even,
+    /// so tiles across that seam are offset by half a tile from where they would otherwise be.
+    Wrap,
+}
+
+impl Border {
+    fn resolve(self, x: isize, y: isize, height: usize, width: usize) -> Option<(usize, usize)> {
+        if height == 0 || width == 0 {
+            return None;
+        }
+        let (height, width) = (height as isize, width as isize);
+        match self {
+            Border::Clamp => Some((
+                x.clamp(0, height - 1) as usize,
+                y.clamp(0, width - 1) as usize,
+            )),
+            Border::Skip => {
+                if (0..height).contains(&x) && (0..width).contains(&y) {
+                    Some((x as usize, y as usize))
+                } else {
+                    None
+                }
+            }
+            Border::Wrap => Some((x.rem_euclid(height) as usize, y.rem_euclid(width) as usize)),
+        }
+    }
+}
+
+impl<T> HexArray<T> {
+    /// Create a new HexArray by applying the given function to every tile.
+    pub fn map<U, F>(&self, f: F) -> HexArray<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        HexArray {
+            height: self.height,
+            width: self.width,
+            tiles: self.tiles.iter().map(f).collect(),
+        }
+    }
+
+    /// Create a new HexArray by combining every tile with the tile at the same indices in `other`.
+    ///
+    /// Returns `None` if the dimensions of the HexArrays differ.
+    pub fn zip_with<U, V, F>(&self, other: &HexArray<U>, mut f: F) -> Option<HexArray<V>>
+    where
+        F: FnMut(&T, &U) -> V,
+    {
+        if self.height != other.height || self.width != other.width {
+            return None;
+        }
+        Some(HexArray {
+            height: self.height,
+            width: self.width,
+            tiles: self
+                .tiles
+                .iter()
+                .zip(other.tiles.iter())
+                .map(|(a, b)| f(a, b))
+                .collect(),
+        })
+    }
+
+    /// Fold every tile into an accumulator, in index order.
+    pub fn fold<B, F>(&self, init: B, f: F) -> B
+    where
+        F: FnMut(B, &T) -> B,
+    {
+        self.tiles.iter().fold(init, f)
+    }
+}
+
+impl HexArray<f32> {
+    /// Apply a weighted kernel over the neighborhood of every tile.
+    ///
+    /// `weights[d]` is the weight of the tiles at distance `d` from the center, for every `d` up
+    /// to `kernel_radius`; missing weights are treated as zero. The weights are not normalized.
+    pub fn convolve(&self, kernel_radius: usize, weights: &[f32], border: Border) -> HexArray<f32> {
+        // tiles beyond the last weight contribute nothing
+        let kernel_radius = kernel_radius.min(weights.len().saturating_sub(1));
+        let mut result = HexArray::new(self.height, self.width, 0.0);
+        for x in 0..self.height {
+            for y in 0..self.width {
+                let (q, r) = to_axial(x as isize, y as isize);
+                let mut sum = 0.0;
+                for (dq, dr, distance) in axial_range(kernel_radius) {
+                    let weight = match weights.get(distance) {
+                        Some(weight) => *weight,
+                        None => continue,
+                    };
+                    let (nx, ny) = from_axial(q + dq, r + dr);
+                    if let Some((nx, ny)) = border.resolve(nx, ny, self.height, self.width) {
+                        sum += weight * self.tiles[nx * self.width + ny];
+                    }
+                }
+                result.tiles[x * self.width + y] = sum;
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // map tests
+
+    #[test]
+    fn test_map() {
+        let mut hex_array = HexArray::new(3, 4, 1);
+        hex_array.set(2, 3, 5);
+        let mapped = hex_array.map(|tile| tile * 2);
+        assert_eq!(mapped.height(), 3);
+        assert_eq!(mapped.width(), 4);
+        assert_eq!(mapped.get(0, 0), Some(&2));
+        assert_eq!(mapped.get(2, 3), Some(&10));
+    }
+
+    // zip_with tests
+
+    #[test]
+    fn test_zip_with() {
+        let mut a = HexArray::new(2, 2, 1);
+        let b = HexArray::new(2, 2, 2.5);
+        a.set(1, 0, 3);
+        let zipped = a.zip_with(&b, |a, b| *a as f32 * b).unwrap();
+        assert_eq!(zipped.get(0, 0), Some(&2.5));
+        assert_eq!(zipped.get(1, 0), Some(&7.5));
+    }
+
+    #[test]
+    fn test_zip_with_mismatched() {
+        let a = HexArray::new(2, 2, 1);
+        let b = HexArray::new(2, 3, 1);
+        assert!(a.zip_with(&b, |a, b| a + b).is_none());
+    }
+
+    // fold tests
+
+    #[test]
+    fn test_fold() {
+        let mut hex_array = HexArray::new(4, 4, 1);
+        hex_array.set(3, 3, 10);
+        assert_eq!(hex_array.fold(0, |acc, tile| acc + tile), 25);
+    }
+
+    // convolve tests
+
+    #[test]
+    fn test_convolve_identity() {
+        let mut hex_array = HexArray::new(3, 3, 0.0);
+        hex_array.set(1, 2, 4.0);
+        let result = hex_array.convolve(0, &[1.0], Border::Skip);
+        assert_eq!(result.get(1, 2), Some(&4.0));
+        assert_eq!(result.get(0, 0), Some(&0.0));
+    }
+
+    #[test]
+    fn test_convolve_skip() {
+        let hex_array = HexArray::new(4, 4, 1.0);
+        let result = hex_array.convolve(1, &[1.0, 1.0], Border::Skip);
+        assert_eq!(result.get(1, 1), Some(&7.0));
+        assert_eq!(result.get(0, 0), Some(&3.0));
+        assert_eq!(result.get(3, 3), Some(&3.0));
+    }
+
+    #[test]
+    fn test_convolve_clamp() {
+        let hex_array = HexArray::new(4, 4, 1.0);
+        let result = hex_array.convolve(1, &[1.0, 1.0], Border::Clamp);
+        assert_eq!(result.get(0, 0), Some(&7.0));
+        assert_eq!(result.get(3, 3), Some(&7.0));
+    }
+
+    #[test]
+    fn test_convolve_wrap() {
+        let mut hex_array = HexArray::new(4, 4, 0.0);
+        hex_array.set(3, 0, 1.0);
+        let result = hex_array.convolve(1, &[0.0, 1.0], Border::Wrap);
+        assert_eq!(result.get(0, 0), Some(&1.0));
+        assert_eq!(result.get(2, 0), Some(&1.0));
+        assert_eq!(result.get(3, 0), Some(&0.0));
+    }
+
+    #[test]
+    fn test_convolve_missing_weights() {
+        let hex_array = HexArray::new(4, 4, 1.0);
+        let result = hex_array.convolve(2, &[1.0], Border::Clamp);
+        assert_eq!(result.get(1, 1), Some(&1.0));
+        let result = hex_array.convolve(usize::MAX, &[1.0], Border::Clamp);
+        assert_eq!(result.get(1, 1), Some(&1.0));
+        let result = hex_array.convolve(usize::MAX, &[1.0, 1.0], Border::Skip);
+        assert_eq!(result.get(1, 1), Some(&7.0));
+        let result = hex_array.convolve(usize::MAX, &[], Border::Clamp);
+        assert_eq!(result.get(1, 1), Some(&0.0));
+    }
+}
@@ -0,0 +1,328 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::{pos, Direction, HexArray, HexEdge, Point};
+
+/// A vertex where three tiles meet.
+///
+/// Corners of a tile are numbered clockwise from 0 to 5, corner `i` lying between the edges in
+/// directions `i` and `i + 1` of `Direction::ALL`. Every vertex has a single canonical
+/// representation: corner 1 (east) or corner 4 (west) of one of its three tiles. The tile indices
+/// of a vertex may be outside of any HexArray.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct HexVertex {
+    x: isize,
+    y: isize,
+    corner: usize,
+}
+
+impl HexVertex {
+    /// Get the given corner of the tile at the given indices.
+    pub fn new(x: usize, y: usize, corner: usize) -> Self {
+        Self::from_tile(x as isize, y as isize, corner)
+    }
+
+    pub(crate) fn from_tile(x: isize, y: isize, corner: usize) -> Self {
+        let corner = corner % 6;
+        match corner {
+            1 | 4 => HexVertex { x, y, corner },
+            0 | 3 => {
+                let (x, y) = Direction::from_index(corner + 1).step(x, y);
+                HexVertex {
+                    x,
+                    y,
+                    corner: (corner + 4) % 6,
+                }
+            }
+            _ => {
+                let (x, y) = Direction::from_index(corner).step(x, y);
+                HexVertex {
+                    x,
+                    y,
+                    corner: (corner + 2) % 6,
+                }
+            }
+        }
+    }
+
+    /// Get the x index of the tile owning the vertex.
+    pub fn x(&self) -> isize {
+        self.x
+    }
+
+    /// Get the y index of the tile owning the vertex.
+    pub fn y(&self) -> isize {
+        self.y
+    }
+
+    /// Get the corner of the tile owning the vertex, either 1 or 4.
+    pub fn corner(&self) -> usize {
+        self.corner
+    }
+
+    /// Get the indices of the three tiles meeting at the vertex.
+    pub fn tiles(&self) -> [(isize, isize); 3] {
+        [
+            (self.x, self.y),
+            Direction::from_index(self.corner).step(self.x, self.y),
+            Direction::from_index(self.corner + 1).step(self.x, self.y),
+        ]
+    }
+
+    /// Get the three edges meeting at the vertex.
+    pub fn edges(&self) -> [HexEdge; 3] {
+        let (nx, ny) = Direction::from_index(self.corner).step(self.x, self.y);
+        [
+            HexEdge::from_tile(self.x, self.y, Direction::from_index(self.corner)),
+            HexEdge::from_tile(self.x, self.y, Direction::from_index(self.corner + 1)),
+            HexEdge::from_tile(nx, ny, Direction::from_index(self.corner + 2)),
+        ]
+    }
+
+    /// Get the three vertices connected to the vertex by an edge.
+    pub fn adjacent(&self) -> [HexVertex; 3] {
+        let (nx, ny) = Direction::from_index(self.corner).step(self.x, self.y);
+        [
+            HexVertex::from_tile(self.x, self.y, self.corner + 5),
+            HexVertex::from_tile(self.x, self.y, self.corner + 1),
+            HexVertex::from_tile(nx, ny, self.corner + 1),
+        ]
+    }
+
+    fn pos(&self) -> (f32, f32) {
+        let (x, y) = pos(self.x, self.y);
+        if self.corner == 1 {
+            (x + 1.0, y)
+        } else {
+            (x - 1.0, y)
+        }
+    }
+
+    /// Get the position of the vertex.
+    pub fn position(&self) -> Point {
+        self.pos().into()
+    }
+}
+
+impl<T> HexArray<T> {
+    /// Get the corners of the tile at the given indices, in clockwise order.
+    pub fn vertices(x: usize, y: usize) -> [HexVertex; 6] {
+        [0, 1, 2, 3, 4, 5].map(|corner| HexVertex::new(x, y, corner))
+    }
+
+    pub(crate) fn corner_pos(x: usize, y: usize) -> [(f32, f32); 6] {
+        Self::vertices(x, y).map(|vertex| vertex.pos())
+    }
+
+    /// Get the positions of the corners of the tile at the given indices, in clockwise order.
+    pub fn corners(x: usize, y: usize) -> [Point; 6] {
+        Self::corner_pos(x, y).map(Point::from)
+    }
+
+    /// Get the indices of the tiles meeting at the given vertex that are inside the HexArray.
+    pub fn vertex_tiles(&self, vertex: HexVertex) -> Vec<(usize, usize)> {
+        vertex
+            .tiles()
+            .into_iter()
+            .filter_map(|(x, y)| self.in_bounds(x, y))
+            .collect()
+    }
+}
+
+/// Storage for a value on every vertex of the tiles of a HexArray with the same dimensions.
+pub struct VertexArray<T> {
+    height: usize,
+    width: usize,
+    vertices: Vec<T>,
+}
+
+impl<T> VertexArray<T>
+where
+    T: Clone,
+{
+    /// Create a new VertexArray for a HexArray with the given height, width, and default value.
+    pub fn new(height: usize, width: usize, default: T) -> Self {
+        VertexArray {
+            height,
+            width,
+            vertices: vec![default; (height + 2) * (width + 2) * 2],
+        }
+    }
+}
+
+impl<T> VertexArray<T> {
+    /// Get the height of the HexArray the VertexArray belongs to.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Get the width of the HexArray the VertexArray belongs to.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    fn index(&self, vertex: HexVertex) -> Option<usize> {
+        let (height, width) = (self.height as isize, self.width as isize);
+        let inside = vertex
+            .tiles()
+            .iter()
+            .any(|(x, y)| (0..height).contains(x) && (0..width).contains(y));
+        if inside {
+            let x = (vertex.x + 1) as usize;
+            let y = (vertex.y + 1) as usize;
+            Some((x * (self.width + 2) + y) * 2 + usize::from(vertex.corner == 4))
+        } else {
+            None
+        }
+    }
+
+    /// Check whether the vertex is a corner of at least one tile of the HexArray.
+    pub fn contains(&self, vertex: HexVertex) -> bool {
+        self.index(vertex).is_some()
+    }
+
+    /// Get the value on the given vertex.
+    pub fn get(&self, vertex: HexVertex) -> Option<&T> {
+        self.index(vertex).map(|index| &self.vertices[index])
+    }
+
+    /// Get a mutable reference to the value on the given vertex.
+    pub fn get_mut(&mut self, vertex: HexVertex) -> Option<&mut T> {
+        self.index(vertex).map(|index| &mut self.vertices[index])
+    }
+
+    /// Set the value on the given vertex.
+    pub fn set(&mut self, vertex: HexVertex, value: T) -> Option<()> {
+        let index = self.index(vertex)?;
+        self.vertices[index] = value;
+        Some(())
+    }
+
+    /// Iterate over every vertex that is a corner of at least one tile of the HexArray.
+    pub fn vertices(&self) -> impl Iterator<Item = HexVertex> + '_ {
+        (-1..=self.height as isize)
+            .flat_map(move |x| (-1..=self.width as isize).map(move |y| (x, y)))
+            .flat_map(|(x, y)| [1, 4].map(|corner| HexVertex { x, y, corner }))
+            .filter(move |vertex| self.contains(*vertex))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // new tests
+
+    #[test]
+    fn test_new_canonical() {
+        assert_eq!(HexVertex::new(2, 1, 0), HexVertex::new(3, 1, 4));
+        assert_eq!(HexVertex::new(2, 1, 2), HexVertex::new(3, 0, 4));
+        assert_eq!(HexVertex::new(2, 1, 3), HexVertex::new(1, 0, 1));
+        assert_eq!(HexVertex::new(2, 1, 5), HexVertex::new(1, 1, 1));
+    }
+
+    #[test]
+    fn test_new_shared() {
+        for x in 1..4 {
+            for y in 1..4 {
+                for corner in 0..6 {
+                    let vertex = HexVertex::new(x, y, corner);
+                    for (tx, ty) in vertex.tiles() {
+                        let shared = HexArray::<i32>::vertices(tx as usize, ty as usize);
+                        assert_eq!(shared.iter().filter(|v| **v == vertex).count(), 1);
+                    }
+                }
+            }
+        }
+    }
+
+    // tiles tests
+
+    #[test]
+    fn test_tiles() {
+        let vertex = HexVertex::new(1, 1, 1);
+        assert_eq!(vertex.tiles(), [(1, 1), (2, 2), (2, 1)]);
+        let hex_array = HexArray::new(2, 4, 0);
+        assert_eq!(hex_array.vertex_tiles(vertex), vec![(1, 1)]);
+    }
+
+    // edges tests
+
+    #[test]
+    fn test_edges() {
+        let vertex = HexVertex::new(1, 1, 1);
+        for edge in vertex.edges() {
+            assert!(edge.vertices().contains(&vertex));
+        }
+    }
+
+    // adjacent tests
+
+    #[test]
+    fn test_adjacent() {
+        let vertex = HexVertex::new(2, 2, 4);
+        let adjacent = vertex.adjacent();
+        for edge in vertex.edges() {
+            let [a, b] = edge.vertices();
+            let other = if a == vertex { b } else { a };
+            assert!(adjacent.contains(&other));
+        }
+    }
+
+    // position tests
+
+    #[test]
+    fn test_position() {
+        assert_eq!(HexVertex::new(0, 0, 1).pos(), (1.0, 0.0));
+        assert_eq!(HexVertex::new(0, 0, 4).pos(), (-1.0, 0.0));
+        let (x, y) = HexVertex::new(0, 0, 0).pos();
+        assert!((x - 0.5).abs() < 1e-6);
+        assert!((y - 0.8660254).abs() < 1e-6);
+    }
+
+    // corners tests
+
+    #[test]
+    fn test_corners() {
+        let corners = HexArray::<i32>::corner_pos(1, 0);
+        assert_eq!(corners[1], (2.5, 0.8660254));
+        assert_eq!(corners[4], (0.5, 0.8660254));
+        for (x, y) in corners {
+            let (dx, dy) = (x - 1.5, y - 0.8660254);
+            assert!(((dx * dx + dy * dy).sqrt() - 1.0).abs() < 1e-5);
+        }
+    }
+
+    // VertexArray tests
+
+    #[test]
+    fn test_vertex_array() {
+        let mut vertex_array = VertexArray::new(4, 4, 0);
+        assert_eq!(vertex_array.set(HexVertex::new(2, 1, 0), 1), Some(()));
+        assert_eq!(vertex_array.get(HexVertex::new(3, 1, 4)), Some(&1));
+        *vertex_array.get_mut(HexVertex::new(3, 1, 4)).unwrap() += 1;
+        assert_eq!(vertex_array.get(HexVertex::new(2, 1, 0)), Some(&2));
+        assert!(vertex_array.contains(HexVertex::new(0, 0, 3)));
+        assert_eq!(vertex_array.get(HexVertex::new(9, 9, 0)), None);
+    }
+
+    #[test]
+    fn test_vertex_array_vertices() {
+        let vertex_array = VertexArray::new(2, 3, 0);
+        let vertices: Vec<_> = vertex_array.vertices().collect();
+        let mut expected = Vec::new();
+        for x in 0..2 {
+            for y in 0..3 {
+                for vertex in HexArray::<i32>::vertices(x, y) {
+                    if !expected.contains(&vertex) {
+                        expected.push(vertex);
+                    }
+                }
+            }
+        }
+        assert_eq!(vertices.len(), expected.len());
+        for vertex in expected {
+            assert!(vertices.contains(&vertex));
+        }
+    }
+}
@@ -0,0 +1,559 @@
+//! Wave Function Collapse on HexArrays.
+//!
+//! Every tile type of a `Tileset` has a label on each of its six sides, and two tiles may be
+//! adjacent when the labels of their touching sides are equal or when `Tileset::allow` allows the
+//! pair in that direction. `Wfc::with_rule` replaces both with an arbitrary rule. `Wfc` starts with
+//! every tile type possible on every tile, repeatedly collapses the most constrained tile to a
+//! random type and propagates the consequences to its neighbors, until every tile has a single type
+//! or a tile has none left.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::error::Error;
+use core::fmt;
+
+use rand::{Rng, RngExt};
+
+use crate::{Direction, HexArray};
+
+/// The index of a tile type in a Tileset.
+pub type TileId = usize;
+
+#[derive(Clone, Debug, PartialEq)]
+struct TileType<L> {
+    sides: [L; 6],
+    weight: f64,
+    base: TileId,
+    rotation: usize,
+}
+
+/// The tile types available to Wave Function Collapse, with the labels of their sides.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tileset<L> {
+    tiles: Vec<TileType<L>>,
+    /// Pairs of tile types allowed next to each other regardless of their labels.
+    allowed: Vec<(TileId, TileId, Direction)>,
+}
+
+impl<L> Default for Tileset<L> {
+    fn default() -> Self {
+        Tileset {
+            tiles: Vec::new(),
+            allowed: Vec::new(),
+        }
+    }
+}
+
+impl<L> Tileset<L>
+where
+    L: Clone + PartialEq,
+{
+    /// Create a new empty Tileset.
+    pub fn new() -> Self {
+        Tileset::default()
+    }
+
+    /// Add a tile type with the given side labels, in the order of `Direction::ALL`.
+    ///
+    /// The weight sets how often the tile type is chosen relative to others.
+    pub fn add(&mut self, sides: [L; 6], weight: f64) -> TileId {
+        let id = self.tiles.len();
+        self.tiles.push(TileType {
+            sides,
+            weight,
+            base: id,
+            rotation: 0,
+        });
+        id
+    }
+
+    /// Add a tile type and its distinct rotations by multiples of 60 degrees clockwise, each with
+    /// the given weight.
+    ///
+    /// Returns the ids of the added tile types, starting with the unrotated one.
+    pub fn add_rotations(&mut self, sides: [L; 6], weight: f64) -> Vec<TileId> {
+        let base = self.add(sides, weight);
+        let mut ids = vec![base];
+        for rotation in 1..6 {
+            let sides: [L; 6] = core::array::from_fn(|direction| {
+                self.tiles[base].sides[(direction + 6 - rotation) % 6].clone()
+            });
+            if ids.iter().any(|&id| self.tiles[id].sides == sides) {
+                continue;
+            }
+            ids.push(self.tiles.len());
+            self.tiles.push(TileType {
+                sides,
+                weight,
+                base,
+                rotation,
+            });
+        }
+        ids
+    }
+}
+
+impl<L> Tileset<L> {
+    /// Get the number of tile types.
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    /// Check whether the Tileset has no tile types.
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    /// Allow tile type `b` in the given direction from tile type `a`, and so `a` in the opposite
+    /// direction from `b`, whatever the labels of their sides.
+    ///
+    /// Returns `None` if either tile type does not exist.
+    pub fn allow(&mut self, a: TileId, b: TileId, direction: Direction) -> Option<()> {
+        if a >= self.tiles.len() || b >= self.tiles.len() {
+            return None;
+        }
+        self.allowed.push((a, b, direction));
+        Some(())
+    }
+
+    /// Get the side labels of the tile type, in the order of `Direction::ALL`.
+    pub fn sides(&self, id: TileId) -> Option<&[L; 6]> {
+        Some(&self.tiles.get(id)?.sides)
+    }
+
+    /// Get the id of the tile type the given one is a rotation of, and the number of 60 degree
+    /// clockwise steps it is rotated by.
+    pub fn rotation(&self, id: TileId) -> Option<(TileId, usize)> {
+        let tile = self.tiles.get(id)?;
+        Some((tile.base, tile.rotation))
+    }
+}
+
+/// An error returned when no tile type is possible on a tile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Contradiction {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl fmt::Display for Contradiction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "no tile type is possible at ({}, {})", self.x, self.y)
+    }
+}
+
+impl Error for Contradiction {}
+
+/// Iterate over the indices of the set bits of the words.
+fn ones(words: &[u64]) -> impl Iterator<Item = usize> + '_ {
+    words.iter().enumerate().flat_map(|(i, &word)| {
+        let mut word = word;
+        core::iter::from_fn(move || {
+            if word == 0 {
+                return None;
+            }
+            let bit = word.trailing_zeros() as usize;
+            word &= word - 1;
+            Some(i * 64 + bit)
+        })
+    })
+}
+
+/// The state of Wave Function Collapse on a HexArray.
+///
+/// Create it from a Tileset, optionally constrain tiles to some tile types, then `run` it. There
+/// is no backtracking: on a contradiction, retry with another seed or fewer constraints.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Wfc {
+    height: usize,
+    width: usize,
+    words: usize,
+    weights: Vec<f64>,
+    /// For every direction and tile type, the tile types allowed next to it in that direction.
+    compatible: Vec<u64>,
+    /// For every tile, the tile types still possible on it.
+    possible: Vec<u64>,
+    pending: Vec<usize>,
+    queued: Vec<bool>,
+    /// For every tile, the number of tile types still possible on it.
+    counts: Vec<usize>,
+    /// For every number of possible tile types, the tiles that had that many when they were
+    /// added. Tiles whose count has changed since are removed lazily.
+    buckets: Vec<Vec<usize>>,
+    contradiction: Option<Contradiction>,
+}
+
+impl Wfc {
+    /// Start Wave Function Collapse on a HexArray of the given dimensions, allowing tile types
+    /// next to each other when their touching sides have equal labels or `Tileset::allow` allows
+    /// them.
+    pub fn new<L>(tileset: &Tileset<L>, height: usize, width: usize) -> Self
+    where
+        L: PartialEq,
+    {
+        let mut wfc = Wfc::with_rule(tileset, height, width, |a, b, direction| {
+            let (a, b) = (&tileset.tiles[a], &tileset.tiles[b]);
+            a.sides[direction.index()] == b.sides[direction.opposite().index()]
+        });
+        for &(a, b, direction) in &tileset.allowed {
+            wfc.allow(a, b, direction);
+        }
+        wfc
+    }
+
+    /// Start Wave Function Collapse on a HexArray of the given dimensions, with a rule deciding
+    /// which tile types may be adjacent.
+    ///
+    /// Tile type `b` may lie in direction `d` from tile type `a` when the rule returns true for
+    /// `(a, b, d)` or for `(b, a, d.opposite())`.
+    ///
+    /// The labels of the Tileset and the pairs allowed with `Tileset::allow` are ignored.
+    pub fn with_rule<L, F>(tileset: &Tileset<L>, height: usize, width: usize, mut rule: F) -> Self
+    where
+        F: FnMut(TileId, TileId, Direction) -> bool,
+    {
+        let count = tileset.tiles.len();
+        let words = count.div_ceil(64);
+        let cells = height * width;
+        let mut all = vec![!0u64; words];
+        if !count.is_multiple_of(64) {
+            all[words - 1] = !0 >> (64 - count % 64);
+        }
+        let mut buckets = vec![Vec::new(); count + 1];
+        if count > 1 {
+            buckets[count] = (0..cells).rev().collect();
+        }
+        let mut wfc = Wfc {
+            height,
+            width,
+            words,
+            weights: tileset.tiles.iter().map(|tile| tile.weight).collect(),
+            compatible: vec![0; 6 * count * words],
+            possible: all.repeat(cells),
+            pending: (0..cells).collect(),
+            queued: vec![true; cells],
+            counts: vec![count; cells],
+            buckets,
+            contradiction: (count == 0 && cells > 0).then_some(Contradiction { x: 0, y: 0 }),
+        };
+        for direction in Direction::ALL {
+            for a in 0..count {
+                for b in 0..count {
+                    if rule(a, b, direction) {
+                        wfc.allow(a, b, direction);
+                    }
+                }
+            }
+        }
+        wfc
+    }
+
+    /// Allow tile type `b` in the given direction from tile type `a`, and `a` in the opposite
+    /// direction from `b`.
+    fn allow(&mut self, a: TileId, b: TileId, direction: Direction) {
+        let count = self.weights.len();
+        let words = self.words;
+        self.compatible[(direction.index() * count + a) * words + b / 64] |= 1 << (b % 64);
+        let opposite = direction.opposite().index();
+        self.compatible[(opposite * count + b) * words + a / 64] |= 1 << (a % 64);
+    }
+
+    /// Get the height of the HexArray.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Get the width of the HexArray.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    fn cell(&self, index: usize) -> &[u64] {
+        &self.possible[index * self.words..(index + 1) * self.words]
+    }
+
+    /// Get the tile types still possible at the given indices.
+    pub fn possible(&self, x: usize, y: usize) -> Option<Vec<TileId>> {
+        if x >= self.height || y >= self.width {
+            return None;
+        }
+        Some(ones(self.cell(x * self.width + y)).collect())
+    }
+
+    /// Restrict the tile at the given indices to the given tile types and propagate the
+    /// consequences, ignoring indices outside the HexArray.
+    pub fn constrain<I>(&mut self, x: usize, y: usize, tiles: I) -> Result<(), Contradiction>
+    where
+        I: IntoIterator<Item = TileId>,
+    {
+        if x < self.height && y < self.width {
+            let mut allowed = vec![0u64; self.words];
+            for tile in tiles {
+                if tile < self.weights.len() {
+                    allowed[tile / 64] |= 1 << (tile % 64);
+                }
+            }
+            self.restrict(x * self.width + y, &allowed);
+        }
+        self.propagate()
+    }
+
+    /// Intersect the possible tile types of a tile with the allowed ones, queueing it if they
+    /// changed and recording a contradiction if none are left.
+    fn restrict(&mut self, index: usize, allowed: &[u64]) {
+        let words = &mut self.possible[index * self.words..(index + 1) * self.words];
+        let mut changed = false;
+        let mut count = 0;
+        for (word, allowed) in words.iter_mut().zip(allowed) {
+            let restricted = *word & allowed;
+            changed |= restricted != *word;
+            count += restricted.count_ones() as usize;
+            *word = restricted;
+        }
+        if changed {
+            self.counts[index] = count;
+            if count > 1 {
+                self.buckets[count].push(index);
+            }
+        }
+        if count == 0 && self.contradiction.is_none() {
+            self.contradiction = Some(Contradiction {
+                x: index / self.width,
+                y: index % self.width,
+            });
+        }
+        if changed && !self.queued[index] {
+            self.queued[index] = true;
+            self.pending.push(index);
+        }
+    }
+
+    fn propagate(&mut self) -> Result<(), Contradiction> {
+        let count = self.weights.len();
+        let mut allowed = vec![0u64; self.words];
+        while let Some(index) = self.pending.pop() {
+            if let Some(contradiction) = self.contradiction {
+                return Err(contradiction);
+            }
+            self.queued[index] = false;
+            let (x, y) = (index / self.width, index % self.width);
+            for direction in Direction::ALL {
+                let (nx, ny) = direction.step(x as isize, y as isize);
+                if !(0..self.height as isize).contains(&nx)
+                    || !(0..self.width as isize).contains(&ny)
+                {
+                    continue;
+                }
+                allowed.fill(0);
+                for tile in ones(self.cell(index)) {
+                    let start = (direction.index() * count + tile) * self.words;
+                    let rule = &self.compatible[start..start + self.words];
+                    for (word, rule) in allowed.iter_mut().zip(rule) {
+                        *word |= rule;
+                    }
+                }
+                self.restrict(nx as usize * self.width + ny as usize, &allowed);
+            }
+        }
+        match self.contradiction {
+            Some(contradiction) => Err(contradiction),
+            None => Ok(()),
+        }
+    }
+
+    /// Pick the tile with the fewest possible tile types left, breaking ties randomly.
+    fn observe<R>(&mut self, rng: &mut R) -> Option<usize>
+    where
+        R: Rng + ?Sized,
+    {
+        // counts only decrease, so every tile is in a bucket at most once
+        for count in 2..self.buckets.len() {
+            let bucket = &mut self.buckets[count];
+            while !bucket.is_empty() {
+                let position = rng.random_range(0..bucket.len());
+                let index = bucket[position];
+                if self.counts[index] == count {
+                    return Some(index);
+                }
+                bucket.swap_remove(position);
+            }
+        }
+        None
+    }
+
+    /// Pick one of the possible tile types of a tile at random, by weight.
+    fn choose<R>(&self, index: usize, rng: &mut R) -> TileId
+    where
+        R: Rng + ?Sized,
+    {
+        let tiles: Vec<_> = ones(self.cell(index)).collect();
+        let weight = |tile: &TileId| self.weights[*tile].max(0.0);
+        let total: f64 = tiles.iter().map(weight).sum();
+        if !(total > 0.0 && total.is_finite()) {
+            return tiles[rng.random_range(0..tiles.len())];
+        }
+        let mut target = rng.random_range(0.0..total);
+        for tile in &tiles {
+            if target < weight(tile) {
+                return *tile;
+            }
+            target -= weight(tile);
+        }
+        tiles[tiles.len() - 1]
+    }
+
+    /// Collapse every tile to a single tile type.
+    pub fn run<R>(mut self, rng: &mut R) -> Result<HexArray<TileId>, Contradiction>
+    where
+        R: Rng + ?Sized,
+    {
+        self.propagate()?;
+        while let Some(index) = self.observe(rng) {
+            let tile = self.choose(index, rng);
+            let mut allowed = vec![0u64; self.words];
+            allowed[tile / 64] |= 1 << (tile % 64);
+            self.restrict(index, &allowed);
+            self.propagate()?;
+        }
+        let cells = self.height * self.width;
+        Ok(HexArray {
+            height: self.height,
+            width: self.width,
+            tiles: (0..cells)
+                .map(|index| ones(self.cell(index)).next().unwrap())
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+
+    /// Land, sea and coast tiles whose land sides face land and sea sides face sea.
+    fn coast() -> Tileset<char> {
+        let mut tileset = Tileset::new();
+        tileset.add(['L'; 6], 2.0);
+        tileset.add(['S'; 6], 2.0);
+        tileset.add_rotations(['L', 'L', 'L', 'S', 'S', 'S'], 1.0);
+        tileset
+    }
+
+    /// Check that the touching sides of every pair of adjacent tiles match.
+    fn assert_consistent(tileset: &Tileset<char>, result: &HexArray<TileId>) {
+        for x in 0..result.height() {
+            for y in 0..result.width() {
+                let sides = tileset.sides(result.tiles[x * result.width() + y]).unwrap();
+                for direction in Direction::ALL {
+                    if let Some((nx, ny)) = result.neighbor(x, y, direction) {
+                        let other = tileset.sides(*result.get(nx, ny).unwrap()).unwrap();
+                        assert_eq!(
+                            sides[direction.index()],
+                            other[direction.opposite().index()]
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    // tileset tests
+
+    #[test]
+    fn test_add_rotations() {
+        let mut tileset = Tileset::new();
+        assert_eq!(tileset.add_rotations([0; 6], 1.0), vec![0]);
+        assert_eq!(tileset.add_rotations([1, 2, 1, 2, 1, 2], 1.0), vec![1, 2]);
+        let ids = tileset.add_rotations([1, 0, 0, 0, 0, 0], 1.0);
+        assert_eq!(ids.len(), 6);
+        assert_eq!(tileset.len(), 9);
+        assert_eq!(tileset.sides(ids[2]), Some(&[0, 0, 1, 0, 0, 0]));
+        assert_eq!(tileset.rotation(ids[2]), Some((ids[0], 2)));
+        assert_eq!(tileset.sides(9), None);
+    }
+
+    #[test]
+    fn test_allow() {
+        let mut tileset = Tileset::new();
+        let land = tileset.add(['L'; 6], 1.0);
+        let sea = tileset.add(['S'; 6], 1.0);
+        assert_eq!(tileset.allow(land, 2, Direction::North), None);
+        // sea may only lie north of land, never south, east or west of it
+        tileset.allow(land, sea, Direction::North).unwrap();
+        let mut wfc = Wfc::new(&tileset, 1, 3);
+        wfc.constrain(0, 0, [land]).unwrap();
+        wfc.constrain(0, 2, [sea]).unwrap();
+        assert_eq!(wfc.possible(0, 1), Some(vec![land, sea]));
+        let mut wfc = Wfc::new(&tileset, 1, 2);
+        assert!(wfc.constrain(0, 1, [land]).is_ok());
+        assert_eq!(
+            wfc.constrain(0, 0, [sea]),
+            Err(Contradiction { x: 0, y: 0 })
+        );
+    }
+
+    #[test]
+    fn test_with_rule() {
+        let mut tileset = Tileset::new();
+        for _ in 0..3 {
+            tileset.add([(); 6], 1.0);
+        }
+        // every tile must differ from its neighbors, like a three-coloring
+        let wfc = Wfc::with_rule(&tileset, 5, 5, |a, b, _| a != b);
+        let result = wfc.run(&mut SmallRng::seed_from_u64(4)).unwrap();
+        for x in 0..5 {
+            for y in 0..5 {
+                for (nx, ny) in result.adjacent(x, y) {
+                    assert_ne!(result.get(x, y), result.get(nx, ny));
+                }
+            }
+        }
+    }
+
+    // run tests
+
+    #[test]
+    fn test_run() {
+        let tileset = coast();
+        let result = Wfc::new(&tileset, 8, 8)
+            .run(&mut SmallRng::seed_from_u64(3))
+            .unwrap();
+        assert_eq!(result.height(), 8);
+        assert_consistent(&tileset, &result);
+        let again = Wfc::new(&tileset, 8, 8)
+            .run(&mut SmallRng::seed_from_u64(3))
+            .unwrap();
+        assert!(again == result);
+    }
+
+    #[test]
+    fn test_constrain() {
+        let tileset = coast();
+        let mut wfc = Wfc::new(&tileset, 6, 6);
+        wfc.constrain(0, 0, [1]).unwrap();
+        wfc.constrain(5, 5, [0]).unwrap();
+        wfc.constrain(9, 9, [0]).unwrap();
+        // the tile north of the sea must be sea or a coast with sea to the south
+        assert_eq!(wfc.possible(0, 1), Some(vec![1, 2, 6, 7]));
+        let result = wfc.run(&mut SmallRng::seed_from_u64(1)).unwrap();
+        assert_eq!(result.get(0, 0), Some(&1));
+        assert_eq!(result.get(5, 5), Some(&0));
+        assert_consistent(&tileset, &result);
+    }
+
+    #[test]
+    fn test_contradiction() {
+        let mut tileset = Tileset::new();
+        tileset.add(['L'; 6], 1.0);
+        tileset.add(['S'; 6], 1.0);
+        let mut wfc = Wfc::new(&tileset, 2, 2);
+        wfc.constrain(0, 0, [0]).unwrap();
+        assert_eq!(wfc.constrain(0, 1, [1]), Err(Contradiction { x: 0, y: 1 }));
+        let result = wfc.run(&mut SmallRng::seed_from_u64(0));
+        assert_eq!(result, Err(Contradiction { x: 0, y: 1 }));
+        let empty = Wfc::new(&Tileset::<char>::new(), 1, 1);
+        assert!(empty.run(&mut SmallRng::seed_from_u64(0)).is_err());
+    }
+}